@@ -0,0 +1,184 @@
+use crate::export::{Crop, ExportOptions};
+use crate::game::{Language, TickRate};
+use std::path::PathBuf;
+
+pub const USAGE: &str = "usage:
+    rust-game [--tick-rate 60|120|240] [--font <font.bdf>] [--lang en|fr|es] [--level <file.level>]...
+    rust-game export <replay> <out.gif | out_dir> [--skip N] [--scale N] [--crop X,Y,W,H]";
+
+pub enum Command {
+    /// Overrides from the command line, anything left as None comes from the settings file.
+    Play {
+        tick_rate: Option<TickRate>,
+        font: Option<PathBuf>,
+        language: Option<Language>,
+        /// Level files offered after the built-in levels.
+        levels: Vec<PathBuf>,
+    },
+    /// Render a recorded run to a gif, or to a directory of png frames.
+    Export {
+        replay: PathBuf,
+        output: PathBuf,
+        options: ExportOptions,
+    },
+}
+
+/// Parses the command line arguments (without the program name).
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Command, String> {
+    let mut args = args.into_iter().peekable();
+
+    match args.peek().map(String::as_str) {
+        Some("export") => {
+            args.next();
+            parse_export(args)
+        }
+        _ => parse_play(args),
+    }
+}
+
+fn parse_play(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let mut tick_rate = None;
+    let mut font = None;
+    let mut language = None;
+    let mut levels = Vec::new();
+
+    while let Some(flag) = args.next() {
+        let value = args.next().ok_or(format!("missing value for `{flag}`"))?;
+        match flag.as_str() {
+            "--tick-rate" => tick_rate = Some(value.parse()?),
+            "--font" => font = Some(value.into()),
+            "--lang" => language = Some(value.parse()?),
+            "--level" => levels.push(value.into()),
+            _ => return Err(format!("unknown option `{flag}`")),
+        }
+    }
+
+    Ok(Command::Play {
+        tick_rate,
+        font,
+        language,
+        levels,
+    })
+}
+
+fn parse_export(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let replay = args.next().ok_or("missing replay path")?;
+    let output = args.next().ok_or("missing output path")?;
+    let mut options = ExportOptions::default();
+
+    while let Some(flag) = args.next() {
+        let value = args.next().ok_or(format!("missing value for `{flag}`"))?;
+        match flag.as_str() {
+            "--skip" => options.frame_skip = parse_number(&flag, &value)?,
+            "--scale" => options.scale = parse_number(&flag, &value)?,
+            "--crop" => {
+                let parts = value
+                    .split(',')
+                    .map(|part| parse_number(&flag, part))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let [x, y, width, height] = parts[..] else {
+                    return Err("`--crop` expects X,Y,W,H".to_string());
+                };
+                options.crop = Some(Crop {
+                    x,
+                    y,
+                    width,
+                    height,
+                });
+            }
+            _ => return Err(format!("unknown option `{flag}`")),
+        }
+    }
+
+    Ok(Command::Export {
+        replay: replay.into(),
+        output: output.into(),
+        options,
+    })
+}
+
+fn parse_number(flag: &str, value: &str) -> Result<u32, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid value `{value}` for `{flag}`"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Command, parse};
+    use crate::export::{Crop, ExportOptions};
+    use crate::game::TickRate;
+    use std::path::PathBuf;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn parses_play_options() {
+        let Ok(Command::Play {
+            tick_rate,
+            font,
+            language,
+            levels,
+        }) = parse(args(
+            "--tick-rate 240 --level a.level --font f.bdf --level b.level",
+        ))
+        else {
+            panic!("expected play");
+        };
+
+        assert_eq!(tick_rate, Some(TickRate::Hz240));
+        assert_eq!(font, Some(PathBuf::from("f.bdf")));
+        assert_eq!(language, None);
+        assert_eq!(levels, [PathBuf::from("a.level"), PathBuf::from("b.level")]);
+    }
+
+    #[test]
+    fn parses_export_options_up_to_their_limits() {
+        let Ok(Command::Export {
+            replay,
+            output,
+            options,
+        }) = parse(args(
+            "export r.replay out.gif --skip 4294967295 --scale 70000 --crop 4294967295,0,10,10",
+        ))
+        else {
+            panic!("expected export");
+        };
+
+        assert_eq!(replay, PathBuf::from("r.replay"));
+        assert_eq!(output, PathBuf::from("out.gif"));
+        assert_eq!(
+            options,
+            ExportOptions {
+                frame_skip: u32::MAX,
+                scale: 70000,
+                crop: Some(Crop {
+                    x: u32::MAX,
+                    y: 0,
+                    width: 10,
+                    height: 10,
+                }),
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_bad_arguments() {
+        for line in [
+            "--tick-rate",
+            "--tick-rate 100",
+            "--volume 5",
+            "export",
+            "export r.replay",
+            "export r.replay out.gif --skip -1",
+            "export r.replay out.gif --skip 4294967296",
+            "export r.replay out.gif --crop 1,2,3",
+            "export r.replay out.gif --crop 1,2,3,x",
+        ] {
+            assert!(parse(args(line)).is_err(), "{line}");
+        }
+    }
+}
@@ -1,9 +1,62 @@
+use crate::game::{
+    CeilingBehaviour, ColliderShape, CurveStage, Difficulty, Language, Palette, PhysicsPreset,
+    PipeBehaviour, ScorePosition, Theme, TickRate,
+};
+use crate::viewport::ScalingMode;
+
 // Clock
-pub const FIXED_DT: f32 = 1.0 / 120.0;
+pub const DEFAULT_TICK_RATE: TickRate = TickRate::Hz120;
+
+// Display
+/// Frame caps F8 cycles through, None is uncapped.
+pub const FPS_CAPS: [Option<u32>; 5] = [None, Some(30), Some(60), Some(120), Some(144)];
+pub const DEFAULT_FPS_CAP: Option<u32> = None;
+
+// Settings
+pub const SETTINGS_FILE_NAME: &str = "settings.cfg";
+
+// Replays
+pub const REPLAY_DIRECTORY: &str = "replays";
+// Bumped whenever a seed generates a different course, older replays can't be played.
+pub const REPLAY_VERSION: u32 = 5;
+// Widest and tallest an exported frame can be after cropping and scaling, in pixels.
+pub const EXPORT_MAX_SIZE: u32 = 4096;
+
+// High scores, kept next to the settings file.
+pub const HIGH_SCORES_FILE_NAME: &str = "high_scores.txt";
+pub const HIGH_SCORES_KEPT: usize = 10;
+
+// Levels
+// Points for flying through a level's coin, passing a pipe is worth one.
+pub const COIN_POINTS: u32 = 5;
+pub const COIN_SIZE: u32 = 16;
+pub const COIN_COLOR: [u8; 4] = [0xFF, 0xC8, 0x28, 0xFF];
+pub const COIN_EDGE_COLOR: [u8; 4] = [0xB4, 0x82, 0x14, 0xFF];
+pub const FINISH_LINE_WIDTH: u32 = 20;
+// The finish line is a chequered column of squares this size.
+pub const FINISH_LINE_SQUARE: u32 = 10;
+pub const FINISH_LINE_LIGHT_COLOR: [u8; 4] = [0xF0, 0xF0, 0xF0, 0xFF];
+pub const FINISH_LINE_DARK_COLOR: [u8; 4] = [0x28, 0x28, 0x28, 0xFF];
+
+// Localization
+pub const DEFAULT_LANGUAGE: Language = Language::English;
+
+// Settings scene
+pub const DEFAULT_VOLUME: u32 = 100;
+pub const VOLUME_STEP: u32 = 10;
+// Window sizes offered, in percent of the playfield.
+pub const WINDOW_SCALES: [u32; 5] = [50, 75, 100, 150, 200];
+pub const DEFAULT_WINDOW_SCALE: u32 = 100;
+pub const DEFAULT_FLAP_KEY: &str = "space";
+pub const DEFAULT_CONSOLE_KEY: &str = "`";
+pub const DEFAULT_THEME: Theme = Theme::Classic;
+pub const DEFAULT_DIFFICULTY: Difficulty = Difficulty::Normal;
 
 // Window size
+// The playfield is always WIDTH x HEIGHT, the window scales it to fit.
 pub const WIDTH: u32 = 900;
 pub const HEIGHT: u32 = 600;
+pub const SCALING_MODE: ScalingMode = ScalingMode::Integer;
 
 // Pipe constants
 pub const PIPE_SPEED: f32 = 80.0;
@@ -11,17 +64,98 @@ pub const PIPE_WIDTH: u32 = 30;
 pub const PIPE_SPACING: u32 = 200;
 pub const PIPE_GAP_BOUND: u32 = 30;
 pub const PIPE_GAP_SIZE: u32 = 125;
+// The moving pipes, by the names level files use for them.
+pub const OSCILLATING_PIPE: PipeBehaviour = PipeBehaviour::Oscillating {
+    amplitude: 40.0,
+    period: 3.0,
+};
+pub const BREATHING_PIPE: PipeBehaviour = PipeBehaviour::Breathing {
+    amount: 20.0,
+    period: 2.0,
+};
+pub const SLIDING_PIPE: PipeBehaviour = PipeBehaviour::SlideIn { seconds: 3.0 };
+// How often each pipe behaviour is picked for a new pipe, by weight.
+pub const PIPE_SPAWN_TABLE: [(u32, PipeBehaviour); 4] = [
+    (6, PipeBehaviour::Static),
+    (2, OSCILLATING_PIPE),
+    (1, BREATHING_PIPE),
+    (1, SLIDING_PIPE),
+];
+// New gaps are kept within reach of the previous one for a bird flapping at most once
+// every FAIR_FLAP_INTERVAL seconds, with the reach scaled by FAIR_REACH_MARGIN.
+pub const FAIR_FLAP_INTERVAL: f32 = 0.25;
+pub const FAIR_REACH_MARGIN: f32 = 0.6;
+// The flight new gaps are placed along holds heights WITNESS_TARGET_STEP pixels apart,
+// switching WITNESS_SWITCH_STEP seconds apart, and keeps WITNESS_MARGIN pixels inside gaps.
+pub const WITNESS_TARGET_STEP: usize = 8;
+pub const WITNESS_SWITCH_STEP: f32 = 0.1;
+pub const WITNESS_MARGIN: f32 = 4.0;
+// Flights checked for a way on past a new gap before one without is taken.
+pub const WITNESS_LOOKAHEAD: usize = 8;
+// With progressive difficulty on, the pipes speed up and their gaps shrink at these scores.
+pub const DIFFICULTY_CURVE: [CurveStage; 5] = [
+    CurveStage {
+        score: 0,
+        speed_scale: 1.0,
+        gap_scale: 1.0,
+    },
+    CurveStage {
+        score: 10,
+        speed_scale: 1.1,
+        gap_scale: 0.95,
+    },
+    CurveStage {
+        score: 25,
+        speed_scale: 1.2,
+        gap_scale: 0.9,
+    },
+    CurveStage {
+        score: 50,
+        speed_scale: 1.3,
+        gap_scale: 0.85,
+    },
+    CurveStage {
+        score: 100,
+        speed_scale: 1.4,
+        gap_scale: 0.8,
+    },
+];
 
 pub const PIPE_R_COLOR: u8 = 0xFF;
 pub const PIPE_G_COLOR: u8 = 0xFF;
 pub const PIPE_B_COLOR: u8 = 0xFF;
 pub const PIPE_A_COLOR: u8 = 0xFF;
 
+// Ground and ceiling constants
+pub const GROUND_HEIGHT: u32 = 40;
+// Top of the ground strip, the bottom of the space the bird can fly in.
+pub const GROUND_Y: u32 = HEIGHT - GROUND_HEIGHT;
+pub const GROUND_STRIPE_WIDTH: u32 = 20;
+pub const GROUND_EDGE_HEIGHT: u32 = 4;
+pub const DEFAULT_CEILING_BEHAVIOUR: CeilingBehaviour = CeilingBehaviour::Kill;
+// Fraction of the bird's speed kept when it bounces off the ceiling.
+pub const CEILING_BOUNCE_RESTITUTION: f32 = 0.5;
+
+pub const GROUND_EDGE_R_COLOR: u8 = 0x5A;
+pub const GROUND_EDGE_G_COLOR: u8 = 0xC8;
+pub const GROUND_EDGE_B_COLOR: u8 = 0x50;
+pub const GROUND_LIGHT_R_COLOR: u8 = 0xD2;
+pub const GROUND_LIGHT_G_COLOR: u8 = 0xB4;
+pub const GROUND_LIGHT_B_COLOR: u8 = 0x78;
+pub const GROUND_DARK_R_COLOR: u8 = 0xB4;
+pub const GROUND_DARK_G_COLOR: u8 = 0x96;
+pub const GROUND_DARK_B_COLOR: u8 = 0x5A;
+pub const GROUND_A_COLOR: u8 = 0xFF;
+
 // Bird constants
 pub const BIRD_START_POSITION_X: u32 = 300;
 pub const BIRD_START_POSITION_Y: u32 = 200;
+// Values for the classic physics preset, see `PhysicsPreset` for the others.
+pub const DEFAULT_PHYSICS_PRESET: PhysicsPreset = PhysicsPreset::Classic;
 pub const BIRD_FLY_SPEED: f32 = 200.0;
-pub const BIRD_GRAVITY_ACCELERATION_SCALE: f32 = 2.0;
+// In pixels per second squared.
+pub const BIRD_GRAVITY_ACCELERATION: f32 = 240.0;
+pub const BIRD_TERMINAL_VELOCITY: f32 = 420.0;
 
 pub const BIRD_HEIGHT: u32 = 30;
 pub const BIRD_WIDTH: u32 = 30;
@@ -31,6 +165,34 @@ pub const BIRD_G_COLOR: u8 = 0xDC;
 pub const BIRD_B_COLOR: u8 = 0x78;
 pub const BIRD_A_COLOR: u8 = 0xFF;
 
+pub const BIRD_EYE_R_COLOR: u8 = 0x28;
+pub const BIRD_EYE_G_COLOR: u8 = 0x28;
+pub const BIRD_EYE_B_COLOR: u8 = 0x28;
+pub const BIRD_BEAK_R_COLOR: u8 = 0xF0;
+pub const BIRD_BEAK_G_COLOR: u8 = 0x82;
+pub const BIRD_BEAK_B_COLOR: u8 = 0x32;
+
+pub const DEFAULT_COLLIDER_SHAPE: ColliderShape = ColliderShape::Circle;
+// The capsule is a horizontal pill slightly flatter than the sprite.
+pub const BIRD_CAPSULE_RADIUS: f32 = 12.0;
+pub const BIRD_CAPSULE_HALF_LENGTH: f32 = 3.0;
+
+// Developer console constants
+pub const CONSOLE_BACKGROUND_COLOR: [u8; 4] = [0x18, 0x18, 0x20, 0xFF];
+pub const CONSOLE_HISTORY_LINES: usize = 3;
+// In characters, keeps the input line on screen.
+pub const CONSOLE_MAX_INPUT: usize = 48;
+// Most updates one `tick` command runs, so a far target can't freeze the window.
+pub const CONSOLE_MAX_FAST_FORWARD: u64 = 36_000;
+
+// Debug overlay constants
+pub const DEBUG_COLOR: [u8; 4] = [0xFF, 0x00, 0xFF, 0xFF];
+pub const DEBUG_FONT_PIXEL_SIZE: u32 = 2;
+pub const DEBUG_SHADOW_COLOR: [u8; 4] = [0x00, 0x00, 0x00, 0xFF];
+pub const DEBUG_STATS_Y: u32 = 50;
+// The velocity vector is drawn as the distance covered in this many seconds.
+pub const DEBUG_VELOCITY_SECONDS: f32 = 0.25;
+
 // UI constants
 pub const FONT_PIXEL_SIZE_SCREEN_PIXELS: u32 = 5;
 pub const FONT_WIDTH_SIZE_PIXEL_MAP_PIXELS: u32 = 7;
@@ -41,6 +203,63 @@ pub const UI_R_COLOR: u8 = 0x78;
 pub const UI_G_COLOR: u8 = 0xB4;
 pub const UI_B_COLOR: u8 = 0xFF;
 pub const UI_A_COLOR: u8 = 0xFF;
+// Behind menu text so it reads over the playfield.
+pub const UI_OUTLINE_COLOR: [u8; 4] = [0x10, 0x20, 0x40, 0xFF];
+pub const UI_HINT_COLOR: [u8; 4] = [0xC8, 0xDC, 0xFF, 0xFF];
+// Menu items that aren't selected and the best score beside the score.
+pub const UI_MUTED_COLOR: [u8; 4] = [0xA0, 0xA8, 0xB8, 0xFF];
+pub const UI_HINT_FONT_PIXEL_SIZE: u32 = 3;
+// Top of the frame rate shown in the top right corner.
+pub const FPS_COUNTER_Y: u32 = 15;
+
+// Colour themes, see `Theme`.
+pub const CLASSIC_PALETTE: Palette = Palette {
+    background: [0x00, 0x00, 0x00, 0x00],
+    text: [UI_R_COLOR, UI_G_COLOR, UI_B_COLOR, UI_A_COLOR],
+    outline: UI_OUTLINE_COLOR,
+    muted: UI_MUTED_COLOR,
+    hint: UI_HINT_COLOR,
+};
+pub const NIGHT_PALETTE: Palette = Palette {
+    background: [0x14, 0x18, 0x30, 0xFF],
+    text: [0xFF, 0xD0, 0x60, 0xFF],
+    outline: [0x05, 0x05, 0x10, 0xFF],
+    muted: [0x80, 0x88, 0xA8, 0xFF],
+    hint: [0xE8, 0xE0, 0xC0, 0xFF],
+};
+pub const CONTRAST_PALETTE: Palette = Palette {
+    background: [0x00, 0x00, 0x00, 0xFF],
+    text: [0xFF, 0xFF, 0xFF, 0xFF],
+    outline: [0x00, 0x00, 0x00, 0xFF],
+    muted: [0xA0, 0xA0, 0xA0, 0xFF],
+    hint: [0xFF, 0xFF, 0x00, 0xFF],
+};
+
+// Menu constants
+pub const MENU_TITLE_Y: u32 = 80;
+pub const MENU_TITLE_FONT_PIXEL_SIZE: u32 = 8;
+// Top of the first item of the main and death menus.
+pub const MENU_TOP_Y: u32 = 220;
+// Top of the lone back item under the high scores.
+pub const MENU_BACK_Y: u32 = 480;
+// Top of the high scores listed above the back item.
+pub const MENU_LIST_Y: u32 = 180;
+pub const MENU_FONT_PIXEL_SIZE: u32 = 4;
+pub const MENU_ITEM_HEIGHT: u32 = 44;
+// How long the menu at the end of a run ignores clicks and enter after it appears.
+pub const MENU_GRACE_SECONDS: f32 = 0.5;
+// Top of the first row of the settings scene.
+pub const SETTINGS_TOP_Y: u32 = 160;
+
+// Score HUD constants
+pub const DEFAULT_SCORE_POSITION: ScorePosition = ScorePosition::TopCentre;
+pub const SCORE_POP_SECONDS: f32 = 0.2;
+// How much bigger the font pixels are at the start of the pop.
+pub const SCORE_POP_EXTRA_PIXEL_SIZE: u32 = 2;
+// Between the score and the best score beside it.
+pub const SCORE_GHOST_GAP: u32 = 15;
+// Between the score and the difficulty stage under it, clear of the pop.
+pub const SCORE_STAGE_GAP: u32 = 12;
 
 /* Pixel maps for digits, letters, and special characters */
 
@@ -282,7 +501,9 @@ pub const LETTERS: [[bool; 63]; 26] = [
 ];
 
 // 7x9 pixel maps for special characters.
-pub const SPECIALS: [[bool; 63]; 5] = [
+// The characters in SPECIALS, in the same order.
+pub const SPECIAL_CHARS: &str = "()!?/._->\"#$%&'*+,:;<=@[\\]^`{|}~";
+pub const SPECIALS: [[bool; 63]; 32] = [
     // (
     font!(
         0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 1,
@@ -307,9 +528,188 @@ pub const SPECIALS: [[bool; 63]; 5] = [
         0, 0, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0,
         0, 0, 0,
     ),
+    // /
     font!(
         0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0,
         0, 1, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0,
         0, 0, 0,
     ),
+    // .
+    font!(
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0,
+    ),
+    // _
+    font!(
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0,
+        0, 0, 0,
+    ),
+    // -
+    font!(
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        1, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0,
+    ),
+    // >
+    font!(
+        0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0,
+        0, 0, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0,
+    ),
+    // "
+    font!(
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 1, 0, 0, 0, 0, 1, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0,
+    ),
+    // #
+    font!(
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 1, 0, 0, 0, 1, 1, 1, 1, 1, 0, 0, 0, 1, 0, 1, 0, 0, 0, 0,
+        1, 0, 1, 0, 0, 0, 1, 1, 1, 1, 1, 0, 0, 0, 1, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0,
+    ),
+    // $
+    font!(
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 1, 1, 1, 1, 0, 0, 1, 0, 1, 0, 0, 0, 0, 0,
+        1, 1, 1, 0, 0, 0, 0, 0, 1, 0, 1, 0, 0, 1, 1, 1, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0,
+    ),
+    // %
+    font!(
+        0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 0, 0, 1, 0, 0, 1, 1, 0, 0, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0,
+        0, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 1, 0, 0, 1, 1, 0, 0, 1, 0, 0, 1, 1, 0, 0, 0, 0, 0,
+        0, 0, 0,
+    ),
+    // &
+    font!(
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 0, 0, 0, 0, 1, 0, 0, 1, 0, 0, 0, 1, 0, 1, 0, 0, 0, 0, 0,
+        1, 0, 0, 0, 0, 0, 1, 0, 1, 0, 1, 0, 0, 1, 0, 0, 1, 0, 0, 0, 0, 1, 1, 0, 1, 0, 0, 0, 0, 0,
+        0, 0, 0,
+    ),
+    // '
+    font!(
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0,
+    ),
+    // *
+    font!(
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 1, 0, 1, 0, 0, 0, 1, 1, 1, 0, 0, 0, 1,
+        1, 1, 1, 1, 0, 0, 0, 1, 1, 1, 0, 0, 0, 1, 0, 1, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0,
+    ),
+    // +
+    font!(
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1,
+        1, 1, 1, 1, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0,
+    ),
+    // ,
+    font!(
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 1, 0,
+        0, 0, 0,
+    ),
+    // :
+    font!(
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0,
+    ),
+    // ;
+    font!(
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 1, 0,
+        0, 0, 0,
+    ),
+    // <
+    font!(
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 1,
+        0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0,
+        0, 0, 0,
+    ),
+    // =
+    font!(
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0,
+    ),
+    // @
+    font!(
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 1, 0, 1, 1, 1, 0, 0, 1,
+        0, 1, 0, 1, 0, 0, 1, 0, 1, 1, 1, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 0, 0, 0, 0, 0,
+        0, 0, 0,
+    ),
+    // [
+    font!(
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0,
+        1, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 1, 1, 1, 0, 0, 0, 0, 0, 0,
+        0, 0, 0,
+    ),
+    // \
+    font!(
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0,
+        0, 1, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0,
+        0, 0, 0,
+    ),
+    // ]
+    font!(
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0,
+        0, 0, 1, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1, 1, 1, 0, 0, 0, 0, 0, 0,
+        0, 0, 0,
+    ),
+    // ^
+    font!(
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 1, 0, 1, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0,
+    ),
+    // `
+    font!(
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0,
+    ),
+    // {
+    font!(
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 1,
+        0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 1, 1, 0, 0, 0, 0, 0, 0,
+        0, 0, 0,
+    ),
+    // |
+    font!(
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0,
+        0, 1, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0,
+    ),
+    // }
+    font!(
+        0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0,
+        0, 0, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0,
+    ),
+    // ~
+    font!(
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 1, 0, 0, 1,
+        0, 1, 0, 1, 0, 0, 1, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0,
+    ),
 ];
+
+// 7x2 marks combined with a letter's pixel map for accented letters.
+// Above the letter, with a blank row between them.
+pub const GRAVE: [bool; 14] = font!(0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0);
+pub const ACUTE: [bool; 14] = font!(0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0);
+pub const CIRCUMFLEX: [bool; 14] = font!(0, 0, 0, 1, 0, 0, 0, 0, 0, 1, 0, 1, 0, 0);
+pub const DIAERESIS: [bool; 14] = font!(0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 1, 0);
+pub const TILDE: [bool; 14] = font!(0, 0, 1, 1, 0, 1, 0, 0, 1, 0, 1, 1, 0, 0);
+// Below the letter.
+pub const CEDILLA: [bool; 14] = font!(0, 0, 0, 1, 0, 0, 0, 0, 0, 1, 1, 0, 0, 0);
+
+// Drawn for characters the font has no glyph for.
+pub const MISSING_GLYPH: [bool; 63] = font!(
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 0, 0, 1, 0, 0, 0, 1, 0, 0, 1, 0, 0, 0, 1, 0, 0, 1, 0, 0,
+    0, 1, 0, 0, 1, 0, 0, 0, 1, 0, 0, 1, 0, 0, 0, 1, 0, 0, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0,
+);
@@ -0,0 +1,272 @@
+use crate::config::{EXPORT_MAX_SIZE, HEIGHT, WIDTH};
+use crate::game::{Game, Replay};
+use std::fs::{self, File};
+use std::io::{BufWriter, Error, ErrorKind::InvalidInput};
+use std::path::Path;
+
+/// Region of the playfield to export, in playfield pixel coordinates.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Crop {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ExportOptions {
+    /// Number of simulation ticks to skip between exported frames.
+    pub frame_skip: u32,
+    /// Integer nearest-neighbour scale factor applied after cropping.
+    pub scale: u32,
+    pub crop: Option<Crop>,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        // Every 4th tick of a 120Hz replay gives a 30fps clip.
+        Self {
+            frame_skip: 3,
+            scale: 1,
+            crop: None,
+        }
+    }
+}
+
+impl ExportOptions {
+    fn crop(&self) -> Crop {
+        self.crop.unwrap_or(Crop {
+            x: 0,
+            y: 0,
+            width: WIDTH,
+            height: HEIGHT,
+        })
+    }
+
+    /// Checks the options, returning the size of the exported frames.
+    fn validate(&self) -> Result<(u32, u32), Error> {
+        let crop = self.crop();
+        if self.scale == 0 {
+            return Err(Error::new(InvalidInput, "scale must be at least 1"));
+        }
+        let inside = |start: u32, length: u32, limit: u32| {
+            length > 0 && start.checked_add(length).is_some_and(|end| end <= limit)
+        };
+        if !inside(crop.x, crop.width, WIDTH) || !inside(crop.y, crop.height, HEIGHT) {
+            return Err(Error::new(
+                InvalidInput,
+                "crop must lie inside the playfield",
+            ));
+        }
+        let scaled = |length: u32| {
+            length
+                .checked_mul(self.scale)
+                .filter(|&size| size <= EXPORT_MAX_SIZE)
+        };
+        match (scaled(crop.width), scaled(crop.height)) {
+            (Some(width), Some(height)) => Ok((width, height)),
+            _ => Err(Error::new(
+                InvalidInput,
+                format!("output must be at most {EXPORT_MAX_SIZE} pixels across"),
+            )),
+        }
+    }
+}
+
+/// Exports the replay as an animated GIF.
+pub fn export_gif(replay: &Replay, path: &Path, options: &ExportOptions) -> Result<(), Error> {
+    let (width, height) = options.validate()?;
+    if width > u16::MAX as u32 || height > u16::MAX as u32 {
+        return Err(Error::new(InvalidInput, "output is too large for a gif"));
+    }
+
+    let file = BufWriter::new(File::create(path)?);
+    let mut encoder =
+        gif::Encoder::new(file, width as u16, height as u16, &[]).map_err(Error::other)?;
+    encoder
+        .set_repeat(gif::Repeat::Infinite)
+        .map_err(Error::other)?;
+
+    // Gif delays are in hundredths of a second, so round the running clock rather than
+    // each frame's duration to keep the clip from drifting.
+    let frame_time = replay.tick_rate.dt() * options.frame_skip.saturating_add(1) as f32;
+    let mut elapsed = 0.0;
+    let mut written_delay = 0;
+
+    for_each_frame(replay, options, |mut pixels| {
+        elapsed += frame_time;
+        let total_delay = (elapsed * 100.0).round() as u32;
+        let mut frame = gif::Frame::from_rgba_speed(width as u16, height as u16, &mut pixels, 30);
+        frame.delay = (total_delay - written_delay) as u16;
+        written_delay = total_delay;
+
+        encoder.write_frame(&frame).map_err(Error::other)
+    })
+}
+
+/// Exports the replay as numbered PNG frames into the given directory.
+/// The frames can be assembled with e.g.
+/// `ffmpeg -framerate 30 -i frame_%05d.png clip.mp4`.
+pub fn export_frames(replay: &Replay, dir: &Path, options: &ExportOptions) -> Result<(), Error> {
+    let (width, height) = options.validate()?;
+    fs::create_dir_all(dir)?;
+
+    let mut index = 0;
+    for_each_frame(replay, options, |pixels| {
+        let path = dir.join(format!("frame_{index:05}.png"));
+        index += 1;
+
+        let mut encoder = png::Encoder::new(BufWriter::new(File::create(path)?), width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&pixels))
+            .map_err(Error::other)
+    })
+}
+
+/// Steps the replay headlessly, handing every exported frame (already cropped and
+/// scaled) to `write`. The final frame is the death screen.
+fn for_each_frame(
+    replay: &Replay,
+    options: &ExportOptions,
+    mut write: impl FnMut(Vec<u8>) -> Result<(), Error>,
+) -> Result<(), Error> {
+    let size = options.validate()?;
+    let mut game = Game::from_replay(replay);
+    let mut frame = vec![0; (WIDTH * HEIGHT * 4) as usize];
+
+    loop {
+        if game.tick().is_multiple_of(options.frame_skip as u64 + 1) || !game.is_playing() {
+            frame.fill(0);
+            game.draw(&mut frame, 1.0);
+            write(transform(&frame, options, size))?;
+        }
+
+        if !game.is_playing() {
+            return Ok(());
+        }
+
+        for _ in 0..replay.flaps_at(game.tick()) {
+            game.space_bar_hit();
+        }
+        game.update(replay.tick_rate.dt());
+    }
+}
+
+/// Crops and scales the frame to the validated output size, making every pixel opaque.
+fn transform(frame: &[u8], options: &ExportOptions, (width, height): (u32, u32)) -> Vec<u8> {
+    let crop = options.crop();
+    let mut out = Vec::with_capacity((width * height * 4) as usize);
+
+    for y in 0..height {
+        let source_y = crop.y + y / options.scale;
+        for x in 0..width {
+            let source_x = crop.x + x / options.scale;
+            let idx = ((source_y * WIDTH + source_x) * 4) as usize;
+            out.extend_from_slice(&frame[idx..idx + 3]);
+            out.push(0xFF);
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Crop, ExportOptions, export_frames, export_gif, transform};
+    use crate::config::{EXPORT_MAX_SIZE, HEIGHT, WIDTH};
+    use crate::game::{Game, TickRate};
+
+    fn crop(x: u32, y: u32, width: u32, height: u32) -> ExportOptions {
+        ExportOptions {
+            crop: Some(Crop {
+                x,
+                y,
+                width,
+                height,
+            }),
+            ..ExportOptions::default()
+        }
+    }
+
+    #[test]
+    fn validate_rejects_crops_and_scales_out_of_bounds() {
+        assert_eq!(
+            ExportOptions::default().validate().unwrap(),
+            (WIDTH, HEIGHT)
+        );
+        assert_eq!(
+            crop(WIDTH - 10, HEIGHT - 10, 10, 10).validate().unwrap(),
+            (10, 10)
+        );
+
+        for options in [
+            crop(u32::MAX, 0, 10, 10),
+            crop(0, u32::MAX, 10, 10),
+            crop(10, 0, u32::MAX, 10),
+            crop(WIDTH - 9, 0, 10, 10),
+            crop(0, 0, 0, 10),
+            ExportOptions {
+                scale: 0,
+                ..ExportOptions::default()
+            },
+            ExportOptions {
+                scale: 70000,
+                ..ExportOptions::default()
+            },
+            ExportOptions {
+                scale: u32::MAX,
+                ..crop(0, 0, 1, 1)
+            },
+            ExportOptions {
+                scale: EXPORT_MAX_SIZE / 10 + 1,
+                ..crop(0, 0, 10, 10)
+            },
+        ] {
+            assert!(options.validate().is_err(), "{options:?}");
+        }
+    }
+
+    #[test]
+    fn transform_crops_then_scales() {
+        // Each pixel's red and green are its playfield x and y.
+        let frame: Vec<u8> = (0..WIDTH * HEIGHT)
+            .flat_map(|i| [(i % WIDTH) as u8, (i / WIDTH) as u8, 7, 0])
+            .collect();
+        let options = ExportOptions {
+            scale: 2,
+            ..crop(10, 20, 2, 1)
+        };
+
+        let size = options.validate().unwrap();
+        assert_eq!(size, (4, 2));
+        let row = [
+            10, 20, 7, 0xFF, 10, 20, 7, 0xFF, 11, 20, 7, 0xFF, 11, 20, 7, 0xFF,
+        ];
+        assert_eq!(transform(&frame, &options, size), [row, row].concat());
+    }
+
+    #[test]
+    fn huge_options_fail_instead_of_panicking() {
+        let replay = Game::with_seed(0).with_tick_rate(TickRate::Hz60).replay();
+        let dir = std::env::temp_dir().join(format!("rust-game-export-{}", std::process::id()));
+
+        let options = ExportOptions {
+            scale: 70000,
+            ..ExportOptions::default()
+        };
+        assert!(export_frames(&replay, &dir.join("frames"), &options).is_err());
+        assert!(export_gif(&replay, &dir.join("crop.gif"), &crop(u32::MAX, 0, 10, 10)).is_err());
+
+        // Only the first frame and the death screen are exported.
+        let options = ExportOptions {
+            frame_skip: u32::MAX,
+            ..crop(0, 0, 10, 10)
+        };
+        std::fs::create_dir_all(&dir).unwrap();
+        export_gif(&replay, &dir.join("skip.gif"), &options).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,116 +1,1195 @@
 mod bird;
+mod bird_physics;
+mod ceiling;
+mod coin;
+mod collider;
 mod collision_box;
+mod console;
+mod difficulty;
 mod draw_utils;
+mod envelope;
+mod finish_line;
+mod font;
+mod ground;
+mod level;
+mod locale;
+mod menu;
 mod pipe;
+mod pipe_behaviour;
+mod replay;
 mod score;
+mod sprite;
+mod text;
+mod theme;
+mod tick_rate;
 mod vector2;
 
-use self::{bird::Bird, pipe::Pipe};
+use self::{
+    bird::Bird, ceiling::Ceiling, coin::Coin, finish_line::FinishLine, ground::Ground, pipe::Pipe,
+};
 use crate::config::{
-    FONT_HEIGHT_PIXEL_MAP_PIXELS, FONT_PIXEL_SIZE_SCREEN_PIXELS, FONT_START_OFFSET, HEIGHT,
-    PIPE_SPACING, PIPE_WIDTH, WIDTH,
+    BIRD_WIDTH, COIN_POINTS, CONSOLE_MAX_FAST_FORWARD, DEBUG_COLOR, DEBUG_FONT_PIXEL_SIZE,
+    DEBUG_SHADOW_COLOR, DEBUG_STATS_Y, DEBUG_VELOCITY_SECONDS, DEFAULT_CEILING_BEHAVIOUR,
+    DEFAULT_COLLIDER_SHAPE, DEFAULT_PHYSICS_PRESET, DEFAULT_SCORE_POSITION, DEFAULT_TICK_RATE,
+    FONT_START_OFFSET, FPS_COUNTER_Y, MENU_BACK_Y, MENU_GRACE_SECONDS, MENU_LIST_Y,
+    MENU_TITLE_FONT_PIXEL_SIZE, MENU_TITLE_Y, MENU_TOP_Y, PIPE_SPAWN_TABLE, PIPE_WIDTH,
+    SETTINGS_TOP_Y, UI_HINT_FONT_PIXEL_SIZE, VOLUME_STEP, WIDTH, WINDOW_SCALES, WITNESS_LOOKAHEAD,
 };
-use crate::game::draw_utils::draw_string;
-use rand::rngs::ThreadRng;
+use crate::game::draw_utils::{draw_line, draw_string_sized};
+use crate::high_scores::HighScores;
+use crate::settings::Settings;
+pub use bird_physics::PhysicsPreset;
+pub use ceiling::CeilingBehaviour;
+use collider::Collider;
+pub use collider::ColliderShape;
+use console::{Console, ConsoleCommand};
+pub use difficulty::{CurveStage, Difficulty};
+use difficulty::{PipeTuning, curve_stage};
+use envelope::{Envelope, Leg, Witness};
+pub use font::{Font, set_font};
+pub use level::Level;
+pub use locale::Language;
+use locale::Strings;
+use menu::{Menu, MenuAction};
+use pipe_behaviour::Gap;
+pub use pipe_behaviour::PipeBehaviour;
+use rand::{Rng, SeedableRng};
+use rand_pcg::Pcg32;
+pub use replay::Replay;
 use score::Score;
-use std::process::exit;
+pub use score::ScorePosition;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use text::{Align, TextStyle, draw_text, measure_text};
+pub use theme::{Palette, Theme};
+pub use tick_rate::TickRate;
+use vector2::Vector2;
+
+#[cfg(test)]
+mod golden_tests;
+
+/// Timing of the host's most recent frame, shown by the debug overlay.
+#[derive(Clone, Copy, Default)]
+pub struct FrameStats {
+    /// Fixed updates run since the previous frame.
+    pub updates: u32,
+    pub frame_time: Duration,
+}
+
+/// What the host should do after the game handled an input.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Outcome {
+    Continue,
+    /// Save the replay of the run that just ended.
+    SaveReplay,
+    /// Save everything and close, the game never exits the process itself.
+    Quit,
+}
 
-#[derive(PartialEq)]
+#[derive(Clone, Copy, PartialEq)]
 enum GameState {
     MainMenu,
     Playing,
     Dead,
+    HighScores,
+    /// Changes the settings, each change applies right away.
+    Settings,
+    /// Lists the levels to pick one to play.
+    Levels,
+    /// The bird reached the end of a level.
+    LevelComplete,
 }
 
 pub struct Game {
     game_state: GameState,
+    /// The menu of the current screen, None while playing.
+    menu: Option<Menu>,
     score: Score,
     bird: Bird,
     pipes: Vec<Pipe>,
-    rng: ThreadRng,
+    /// The level's coins that have scrolled in, collected ones included.
+    coins: Vec<Coin>,
+    /// The level's finish line once it has scrolled in.
+    finish_line: Option<FinishLine>,
+    ground: Ground,
+    ceiling: Ceiling,
+    seed: u64,
+    /// Whether a run has been played on the seed, the next run picks a fresh one if so.
+    seed_used: bool,
+    rng: Pcg32,
+    tick_rate: TickRate,
+    physics_preset: PhysicsPreset,
+    collider_shape: ColliderShape,
+    difficulty: Difficulty,
+    /// Whether the pipes speed up and narrow as the score rises.
+    progressive: bool,
+    /// How far along the progressive curve the current run is.
+    stage: usize,
+    /// Levels that can be picked from the level list.
+    levels: Vec<Level>,
+    /// Index of the level being played, None for endless runs.
+    level: Option<usize>,
+    /// How far the level has scrolled since the run started.
+    level_distance: f32,
+    /// How many of the level's pipes have scrolled in.
+    level_pipes_spawned: usize,
+    score_position: ScorePosition,
+    strings: Strings,
+    /// The player's settings, changed from the settings scene.
+    settings: Settings,
+    /// Whether the settings changed since the host last saved them.
+    settings_changed: bool,
+    /// The settings row waiting for a key to bind, if any.
+    rebinding: Option<MenuAction>,
+    /// Seconds left before the menu at the end of a run takes clicks and enter, so input
+    /// meant for the bird can't pick an item.
+    menu_grace: f32,
+    /// Scores of finished runs, the best one is shown next to the score.
+    high_scores: HighScores,
+    /// Whether a finished run made the high scores since the host last saved them.
+    high_scores_changed: bool,
+    /// Whether the console was used during the current run, which keeps its score out of
+    /// the high scores.
+    used_console: bool,
+    /// Whether collision shapes and stats are drawn over the game.
+    debug_overlay: bool,
+    frame_stats: FrameStats,
+    console: Console,
+    /// Set from the console, the bird can't die while it is on.
+    god_mode: bool,
+    /// How fast the host should run the clock, set from the console.
+    timescale: f32,
+    /// Number of updates since the current run started.
+    tick: u64,
+    /// Ticks at which the bird flapped during the current run.
+    flaps: Vec<u64>,
+    /// A flight through every gap placed so far, new gaps are placed along it.
+    witness: Witness,
 }
 
 impl Game {
     pub fn new() -> Self {
-        let mut rng = rand::rng();
-        let mut pipes = Vec::new();
+        Self::with_seed(rand::random())
+    }
+
+    /// Creates a game whose first run uses the given seed for pipe generation.
+    /// Runs started after a death pick a fresh random seed.
+    pub fn with_seed(seed: u64) -> Self {
+        let mut rng = Pcg32::seed_from_u64(seed);
         // Create first pipe to start creation loop.
-        pipes.push(Pipe::new(&mut rng));
+        let settings = Settings::default();
+        let pipes = vec![Pipe::new(
+            &mut rng,
+            settings.difficulty.pipes(),
+            f32::NEG_INFINITY..f32::INFINITY,
+        )];
+        let witness = Witness::touching(pipes[0].placed_gap(), 0.0);
 
-        Self {
+        let mut game = Self {
             game_state: GameState::MainMenu,
+            menu: None,
             score: Score::new(),
-            bird: Bird::new(),
+            bird: Bird::new(DEFAULT_PHYSICS_PRESET.physics(), DEFAULT_COLLIDER_SHAPE),
             pipes,
+            coins: Vec::new(),
+            finish_line: None,
+            ground: Ground::new(),
+            ceiling: Ceiling::new(DEFAULT_CEILING_BEHAVIOUR),
+            seed,
+            seed_used: false,
             rng,
-        }
+            tick_rate: DEFAULT_TICK_RATE,
+            physics_preset: DEFAULT_PHYSICS_PRESET,
+            collider_shape: DEFAULT_COLLIDER_SHAPE,
+            difficulty: settings.difficulty,
+            progressive: settings.progressive,
+            stage: 0,
+            levels: Level::built_in(),
+            level: None,
+            level_distance: 0.0,
+            level_pipes_spawned: 0,
+            score_position: DEFAULT_SCORE_POSITION,
+            strings: Strings::new(settings.language),
+            settings,
+            settings_changed: false,
+            rebinding: None,
+            menu_grace: 0.0,
+            high_scores: HighScores::default(),
+            high_scores_changed: false,
+            used_console: false,
+            debug_overlay: false,
+            frame_stats: FrameStats::default(),
+            console: Console::new(),
+            god_mode: false,
+            timescale: 1.0,
+            witness,
+            tick: 0,
+            flaps: Vec::new(),
+        };
+        game.enter(GameState::MainMenu);
+        game
+    }
+
+    /// Sets how many updates per second the host runs.
+    /// Only used to label replays, the host still passes `tick_rate.dt()` to `update`.
+    pub fn with_tick_rate(mut self, tick_rate: TickRate) -> Self {
+        self.tick_rate = tick_rate;
+        self
+    }
+
+    /// Sets the physics used by the bird from the next run on.
+    pub fn with_physics_preset(mut self, physics_preset: PhysicsPreset) -> Self {
+        self.physics_preset = physics_preset;
+        self.bird = Bird::new(physics_preset.physics(), self.collider_shape);
+        self
+    }
+
+    /// Sets the shape the bird collides with from the next run on.
+    pub fn with_collider_shape(mut self, collider_shape: ColliderShape) -> Self {
+        self.collider_shape = collider_shape;
+        self.bird = Bird::new(self.physics_preset.physics(), collider_shape);
+        self
+    }
+
+    /// Sets what happens when the bird flies into the top of the playfield.
+    pub fn with_ceiling_behaviour(mut self, ceiling_behaviour: CeilingBehaviour) -> Self {
+        self.ceiling = Ceiling::new(ceiling_behaviour);
+        self
+    }
+
+    pub fn with_score_position(mut self, score_position: ScorePosition) -> Self {
+        self.score_position = score_position;
+        self
+    }
+
+    pub fn with_language(mut self, language: Language) -> Self {
+        self.strings = Strings::new(language);
+        self.enter(self.game_state);
+        self
+    }
+
+    /// Sets how fast the pipes are and how wide their gaps from the next run on.
+    pub fn with_difficulty(mut self, difficulty: Difficulty) -> Self {
+        self.difficulty = difficulty;
+        self
+    }
+
+    /// Sets whether the pipes follow the progressive curve from the next run on.
+    pub fn with_progressive(mut self, progressive: bool) -> Self {
+        self.progressive = progressive;
+        self
+    }
+
+    /// Applies the player's settings and keeps them for the settings scene.
+    pub fn with_settings(self, settings: Settings) -> Self {
+        let mut game = self
+            .with_physics_preset(settings.physics)
+            .with_ceiling_behaviour(settings.ceiling)
+            .with_collider_shape(settings.collider)
+            .with_score_position(settings.score_position)
+            .with_difficulty(settings.difficulty)
+            .with_progressive(settings.progressive)
+            .with_language(settings.language);
+        game.settings = settings;
+        game
+    }
+
+    /// Sets the high scores that finished runs are recorded into, usually the saved ones.
+    pub fn with_high_scores(mut self, high_scores: HighScores) -> Self {
+        self.high_scores = high_scores;
+        self
+    }
+
+    /// Sets the levels offered in the level list, the built-in ones by default.
+    pub fn with_levels(mut self, levels: Vec<Level>) -> Self {
+        self.levels = levels;
+        self.enter(self.game_state);
+        self
+    }
+
+    /// Creates a game that is ready to play back the given replay.
+    /// Feed it the replay's inputs with `Replay::flaps_at` before each update.
+    pub fn from_replay(replay: &Replay) -> Self {
+        let mut game = Self::with_seed(replay.seed)
+            .with_tick_rate(replay.tick_rate)
+            .with_physics_preset(replay.physics_preset)
+            .with_ceiling_behaviour(replay.ceiling_behaviour)
+            .with_collider_shape(replay.collider_shape)
+            .with_difficulty(replay.difficulty)
+            .with_progressive(replay.progressive);
+        game.start_run();
+        game
     }
 
     fn refresh_state(&mut self) -> () {
-        self.rng = rand::rng();
+        self.rng = Pcg32::seed_from_u64(self.seed);
         self.score = Score::new();
-        self.bird = Bird::new();
-        
+        self.bird = Bird::new(self.physics_preset.physics(), self.collider_shape);
+        self.ground = Ground::new();
+        self.tick = 0;
+        self.flaps = Vec::new();
+        self.used_console = false;
+        self.stage = 0;
+        self.coins = Vec::new();
+        self.finish_line = None;
+        self.level_distance = 0.0;
+        self.level_pipes_spawned = 0;
+
         self.pipes = Vec::new();
-        // Create first pipe to start creation loop.
-        self.pipes.push(Pipe::new(&mut self.rng));
+        if self.level.is_some() {
+            self.spawn_from_level();
+        } else {
+            // Create first pipe to start creation loop.
+            self.spawn_pipe();
+        }
     }
 
     pub fn update(&mut self, dt: f32) -> () {
-        if self.game_state != GameState::Playing {
-            return;
+        self.menu_grace = (self.menu_grace - dt).max(0.0);
+
+        match self.game_state {
+            GameState::MainMenu
+            | GameState::HighScores
+            | GameState::Settings
+            | GameState::Levels
+            | GameState::LevelComplete => return,
+            GameState::Playing => (),
+            GameState::Dead => {
+                // The world stops, but the bird still falls until it lands.
+                self.bird.update(dt);
+                self.keep_bird_above_ground();
+                return;
+            }
         }
 
         self.bird.update(dt);
+        self.ceiling.push_back(&mut self.bird);
+        let speed = self.speed();
+        self.ground.update(dt, speed);
+        self.score.update(dt);
 
-        self.check_for_new_pipe();
+        if self.level.is_some() {
+            self.spawn_from_level();
+            self.level_distance += speed * dt;
+        } else {
+            self.check_for_new_pipe();
+        }
         for pipe in &mut self.pipes {
             pipe.update(dt);
         }
+        for coin in &mut self.coins {
+            coin.update(dt);
+        }
+        if let Some(finish_line) = &mut self.finish_line {
+            finish_line.update(dt);
+        }
 
         self.check_if_bird_passed_pipe();
+        self.check_if_bird_collects_coins();
+        self.check_for_new_stage();
 
         self.clean_up_past_pipes();
 
-        if self.check_if_bird_dies() {
-            self.game_state = GameState::Dead;
+        if self.god_mode {
+            // Nothing kills the bird, but it still can't fall out of the world.
+            self.keep_bird_above_ground();
+        } else if self.check_if_bird_dies() {
+            self.enter(GameState::Dead);
+            self.keep_bird_above_ground();
+
+            // Levels only keep the score of finished runs.
+            if self.level.is_none()
+                && !self.used_console
+                && self.high_scores.record(self.score.score)
+            {
+                self.high_scores_changed = true;
+            }
+        }
+
+        if self.game_state == GameState::Playing && self.check_if_bird_finished() {
+            self.enter(GameState::LevelComplete);
+
+            let name = &self.levels[self.level.unwrap()].name;
+            if !self.used_console && self.high_scores.record_level(name, self.score.score) {
+                self.high_scores_changed = true;
+            }
         }
+
+        self.tick += 1;
     }
 
-    pub fn draw(&self, frame: &mut [u8]) -> () {
+    /// Draws the current state.
+    /// `alpha` is how far (0 to 1) the clock is between the last update and the next one,
+    /// moving entities are drawn that far along from their previous position so that
+    /// rendering stays smooth when the display rate doesn't match the update rate.
+    pub fn draw(&self, frame: &mut [u8], alpha: f32) -> () {
+        let palette = self.palette();
+        for pixel in frame.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&palette.background);
+        }
+
         match self.game_state {
             GameState::MainMenu => self.draw_main_menu(frame),
-            GameState::Playing => self.draw_playing(frame),
-            GameState::Dead => self.draw_dead(frame),
+            GameState::Playing => self.draw_playing(frame, alpha),
+            GameState::Dead => self.draw_dead(frame, alpha),
+            GameState::HighScores => self.draw_high_scores(frame),
+            GameState::Settings => self.draw_settings(frame),
+            GameState::Levels => self.draw_levels(frame),
+            GameState::LevelComplete => self.draw_level_complete(frame, alpha),
+        }
+
+        if let Some(menu) = &self.menu {
+            menu.draw(frame, &palette);
+        }
+
+        if self.settings.show_fps {
+            self.draw_fps(frame);
+        }
+
+        if self.debug_overlay
+            && matches!(
+                self.game_state,
+                GameState::Playing | GameState::Dead | GameState::LevelComplete
+            )
+        {
+            self.draw_debug_overlay(frame);
+        }
+
+        if self.console.is_open() {
+            self.console.draw(frame);
         }
     }
 
-    pub fn space_bar_hit(&mut self) -> () {
-        self.bird.fly();
+    pub fn toggle_debug_overlay(&mut self) -> () {
+        self.debug_overlay = !self.debug_overlay;
     }
 
-    pub fn y_key_hit(&mut self) -> () {
-        match self.game_state {
-            GameState::Playing => return,
-            _ => {
+    pub fn console(&mut self) -> &mut Console {
+        &mut self.console
+    }
+
+    pub fn console_is_open(&self) -> bool {
+        self.console.is_open()
+    }
+
+    /// Runs the line typed into the console and shows the result under it.
+    pub fn submit_console(&mut self) -> () {
+        let Some(line) = self.console.submit() else {
+            return;
+        };
+
+        let output = match line.parse() {
+            Ok(command) => {
+                let output = self.run_console_command(command);
+                self.used_console = true;
+                output
+            }
+            Err(message) => message,
+        };
+        self.console.set_output(output);
+    }
+
+    /// Multiplier the host applies to the time it feeds to `update`.
+    pub fn timescale(&self) -> f32 {
+        self.timescale
+    }
+
+    /// Applies a console command and returns what to tell the user.
+    /// Console changes aren't recorded, so a replay of a run that used them won't
+    /// play back the same.
+    fn run_console_command(&mut self, command: ConsoleCommand) -> String {
+        let preset = self.physics_preset.physics();
+
+        match command {
+            ConsoleCommand::SetGravity(scale) => {
+                self.bird.physics_mut().gravity = preset.gravity * scale;
+                format!("gravity is {scale} times the preset until the next run")
+            }
+            ConsoleCommand::SetFlapSpeed(scale) => {
+                self.bird.physics_mut().flap_speed = preset.flap_speed * scale;
+                format!("flap speed is {scale} times the preset until the next run")
+            }
+            ConsoleCommand::Seed(seed) => {
+                self.seed = seed;
+                self.seed_used = true;
                 self.refresh_state();
-                self.game_state = GameState::Playing;
+                self.enter(GameState::Playing);
+                format!("restarted with seed {seed}")
+            }
+            ConsoleCommand::God(on) => {
+                self.god_mode = on;
+                format!("god mode {}", if on { "on" } else { "off" })
+            }
+            ConsoleCommand::SpawnPipe => {
+                self.spawn_pipe();
+                "spawned a pipe".to_string()
+            }
+            ConsoleCommand::Score(score) => {
+                self.score.score = score;
+                format!("score set to {score}")
+            }
+            ConsoleCommand::Tick(tick) => {
+                if !self.is_playing() {
+                    return "start a run first".to_string();
+                }
+                let target = tick.min(self.tick + CONSOLE_MAX_FAST_FORWARD);
+                while self.is_playing() && self.tick < target {
+                    self.update(self.tick_rate.dt());
+                }
+                if target < tick {
+                    format!(
+                        "now at tick {}, at most {CONSOLE_MAX_FAST_FORWARD} ticks at a time",
+                        self.tick
+                    )
+                } else {
+                    format!("now at tick {}", self.tick)
+                }
+            }
+            ConsoleCommand::Timescale(timescale) => {
+                self.timescale = timescale;
+                format!("timescale {timescale}")
             }
         }
     }
 
-    pub fn n_key_hit(&mut self) -> () {
+    /// Records how the host's last frame went, for the debug overlay and frame rate.
+    pub fn set_frame_stats(&mut self, frame_stats: FrameStats) -> () {
+        self.frame_stats = frame_stats;
+    }
+
+    pub fn space_bar_hit(&mut self) -> () {
+        if self.game_state == GameState::Playing {
+            self.flaps.push(self.tick);
+            self.bird.fly();
+        }
+    }
+
+    /// Starts a run, on the game's seed the first time and on a fresh one after that.
+    pub fn start_run(&mut self) -> () {
+        if self.seed_used {
+            self.seed = rand::random();
+        }
+        self.seed_used = true;
+        self.refresh_state();
+        self.enter(GameState::Playing);
+    }
+
+    /// Moves the menu selection up.
+    pub fn up_key_hit(&mut self) -> () {
+        if let Some(menu) = &mut self.menu {
+            menu.previous();
+        }
+    }
+
+    /// Moves the menu selection down.
+    pub fn down_key_hit(&mut self) -> () {
+        if let Some(menu) = &mut self.menu {
+            menu.next();
+        }
+    }
+
+    /// Picks the selected menu item.
+    pub fn enter_key_hit(&mut self) -> Outcome {
+        if self.menu_grace > 0.0 {
+            return Outcome::Continue;
+        }
+        match self.menu.as_ref().map(Menu::selected) {
+            Some(action) => self.act(action),
+            None => Outcome::Continue,
+        }
+    }
+
+    /// Changes the selected setting to the previous value.
+    pub fn left_key_hit(&mut self) -> () {
+        if let Some(action) = self.menu.as_ref().map(Menu::selected) {
+            self.change_setting(action, -1);
+        }
+    }
+
+    /// Changes the selected setting to the next value.
+    pub fn right_key_hit(&mut self) -> () {
+        if let Some(action) = self.menu.as_ref().map(Menu::selected) {
+            self.change_setting(action, 1);
+        }
+    }
+
+    /// Leaves the high scores, settings, levels or end of run screens for the main menu.
+    /// Also stops waiting for a key to bind.
+    pub fn escape_key_hit(&mut self) -> () {
+        if self.is_rebinding() {
+            self.bind_key(None);
+            return;
+        }
+
         match self.game_state {
-            GameState::Playing => return,
-            _ => exit(0),
+            GameState::HighScores
+            | GameState::Settings
+            | GameState::Dead
+            | GameState::Levels
+            | GameState::LevelComplete => self.enter(GameState::MainMenu),
+            GameState::MainMenu | GameState::Playing => (),
+        }
+    }
+
+    /// Selects the menu item under the pointer, given in playfield coordinates.
+    pub fn pointer_moved(&mut self, x: f32, y: f32) -> () {
+        if let Some(menu) = &mut self.menu {
+            menu.hover(x, y);
+        }
+    }
+
+    /// Flaps while playing, otherwise picks the menu item that was clicked.
+    pub fn click(&mut self, x: f32, y: f32) -> Outcome {
+        if self.game_state == GameState::Playing {
+            self.space_bar_hit();
+        } else if self.menu_grace > 0.0 {
+            return Outcome::Continue;
+        } else if let Some(action) = self.menu.as_mut().and_then(|menu| menu.click(x, y)) {
+            return self.act(action);
+        }
+        Outcome::Continue
+    }
+
+    /// Does what the menu item says, returning what is left for the host to do.
+    fn act(&mut self, action: MenuAction) -> Outcome {
+        match action {
+            MenuAction::Play => {
+                self.level = None;
+                self.start_run();
+            }
+            MenuAction::Retry => self.start_run(),
+            MenuAction::Level(index) => {
+                self.level = Some(index);
+                self.start_run();
+            }
+            MenuAction::DailyChallenge => {
+                self.level = None;
+                self.seed = daily_seed();
+                self.seed_used = false;
+                self.start_run();
+            }
+            MenuAction::HighScores => self.enter(GameState::HighScores),
+            MenuAction::Levels => self.enter(GameState::Levels),
+            MenuAction::Settings => self.enter(GameState::Settings),
+            MenuAction::MainMenu | MenuAction::Back => self.enter(GameState::MainMenu),
+            MenuAction::SaveReplay => return Outcome::SaveReplay,
+            MenuAction::Quit => return Outcome::Quit,
+            MenuAction::FlapKey | MenuAction::ConsoleKey => {
+                self.rebinding = Some(action);
+                self.enter(self.game_state);
+            }
+            MenuAction::Volume
+            | MenuAction::WindowScale
+            | MenuAction::Theme
+            | MenuAction::Difficulty
+            | MenuAction::Progressive
+            | MenuAction::ShowFps => self.change_setting(action, 1),
+        }
+        Outcome::Continue
+    }
+
+    /// Moves the setting on a settings row `step` values along, wrapping around except
+    /// for the volume, which stops at silent and full. Other rows are left alone.
+    fn change_setting(&mut self, action: MenuAction, step: i32) -> () {
+        let settings = &mut self.settings;
+        match action {
+            MenuAction::Volume => {
+                let volume = settings.volume as i32 + step * VOLUME_STEP as i32;
+                settings.volume = volume.clamp(0, 100) as u32;
+            }
+            MenuAction::WindowScale => {
+                settings.display.window_scale =
+                    cycle(&WINDOW_SCALES, settings.display.window_scale, step);
+            }
+            MenuAction::Theme => settings.theme = cycle(&Theme::ALL, settings.theme, step),
+            MenuAction::Difficulty => {
+                settings.difficulty = cycle(&Difficulty::ALL, settings.difficulty, step);
+                self.difficulty = settings.difficulty;
+            }
+            MenuAction::Progressive => {
+                settings.progressive = !settings.progressive;
+                self.progressive = settings.progressive;
+            }
+            MenuAction::ShowFps => settings.show_fps = !settings.show_fps,
+            _ => return,
+        }
+
+        self.settings_changed = true;
+        self.enter(self.game_state);
+    }
+
+    /// Returns whether the settings scene is waiting for a key to bind.
+    pub fn is_rebinding(&self) -> bool {
+        self.rebinding.is_some()
+    }
+
+    /// Binds the named key to the row waiting for one, or stops waiting if None.
+    /// A key already bound to the other row is refused, and the row keeps waiting.
+    pub fn bind_key(&mut self, key: Option<String>) -> () {
+        let Some(action) = self.rebinding else {
+            return;
+        };
+        let keys = &self.settings.keys;
+        let taken = match action {
+            MenuAction::FlapKey => &keys.console,
+            _ => &keys.flap,
+        };
+        if key.as_ref() == Some(taken) {
+            return;
+        }
+        self.rebinding = None;
+
+        if let Some(key) = key {
+            match action {
+                MenuAction::FlapKey => self.settings.keys.flap = key,
+                MenuAction::ConsoleKey => self.settings.keys.console = key,
+                _ => (),
+            }
+            self.settings_changed = true;
+        }
+        self.enter(self.game_state);
+    }
+
+    /// Switches to the state along with its menu.
+    /// Entering the current state again rebuilds its menu, keeping the selection.
+    fn enter(&mut self, game_state: GameState) -> () {
+        let selected = match &self.menu {
+            Some(menu) if self.game_state == game_state => menu.selected_index(),
+            _ => 0,
+        };
+        let label = |id: &str| self.strings.get(id, &[]);
+
+        let menu = match game_state {
+            GameState::MainMenu => Some(Menu::new(
+                MENU_TOP_Y as i32,
+                vec![
+                    (label("menu.play"), MenuAction::Play),
+                    (label("menu.daily"), MenuAction::DailyChallenge),
+                    (label("menu.high_scores"), MenuAction::HighScores),
+                    (label("menu.levels"), MenuAction::Levels),
+                    (label("menu.settings"), MenuAction::Settings),
+                    (label("menu.quit"), MenuAction::Quit),
+                ],
+            )),
+            GameState::Playing => None,
+            GameState::Dead => {
+                let mut items = vec![
+                    (label("dead.retry"), MenuAction::Retry),
+                    (label("dead.menu"), MenuAction::MainMenu),
+                ];
+                // Replays only record endless runs.
+                if self.level.is_none() {
+                    items.push((label("dead.save_replay"), MenuAction::SaveReplay));
+                }
+                items.push((label("menu.quit"), MenuAction::Quit));
+                Some(Menu::new(MENU_TOP_Y as i32, items))
+            }
+            GameState::HighScores => Some(Menu::new(
+                MENU_BACK_Y as i32,
+                vec![(label("back"), MenuAction::Back)],
+            )),
+            GameState::Settings => Some(self.settings_menu()),
+            GameState::Levels => Some(self.levels_menu()),
+            GameState::LevelComplete => {
+                let mut items = Vec::new();
+                let next = self.level.map_or(0, |index| index + 1);
+                if next < self.levels.len() {
+                    items.push((label("complete.next"), MenuAction::Level(next)));
+                }
+                items.push((label("dead.retry"), MenuAction::Retry));
+                items.push((label("dead.menu"), MenuAction::MainMenu));
+                Some(Menu::new(MENU_TOP_Y as i32, items))
+            }
+        };
+
+        if self.game_state != game_state
+            && matches!(game_state, GameState::Dead | GameState::LevelComplete)
+        {
+            self.menu_grace = MENU_GRACE_SECONDS;
+        }
+        self.game_state = game_state;
+        self.menu = menu.map(|menu| menu.with_selected(selected));
+    }
+
+    /// A row for each setting showing its value, then back.
+    fn settings_menu(&self) -> Menu {
+        let settings = &self.settings;
+        let strings = &self.strings;
+        let row = |id: &str, value: String| strings.get(id, &[("value", &value)]);
+        let key = |action: MenuAction, key: &str| {
+            if self.rebinding == Some(action) {
+                strings.get("settings.press_key", &[])
+            } else {
+                key.to_string()
+            }
+        };
+        let on_off = |on: bool| strings.get(if on { "settings.on" } else { "settings.off" }, &[]);
+
+        Menu::new(
+            SETTINGS_TOP_Y as i32,
+            vec![
+                (
+                    row("settings.volume", settings.volume.to_string()),
+                    MenuAction::Volume,
+                ),
+                (
+                    row(
+                        "settings.flap_key",
+                        key(MenuAction::FlapKey, &settings.keys.flap),
+                    ),
+                    MenuAction::FlapKey,
+                ),
+                (
+                    row(
+                        "settings.console_key",
+                        key(MenuAction::ConsoleKey, &settings.keys.console),
+                    ),
+                    MenuAction::ConsoleKey,
+                ),
+                (
+                    row(
+                        "settings.window_scale",
+                        settings.display.window_scale.to_string(),
+                    ),
+                    MenuAction::WindowScale,
+                ),
+                (
+                    row(
+                        "settings.theme",
+                        strings.get(&format!("theme.{}", settings.theme), &[]),
+                    ),
+                    MenuAction::Theme,
+                ),
+                (
+                    row(
+                        "settings.difficulty",
+                        strings.get(&format!("difficulty.{}", settings.difficulty), &[]),
+                    ),
+                    MenuAction::Difficulty,
+                ),
+                (
+                    row("settings.progressive", on_off(settings.progressive)),
+                    MenuAction::Progressive,
+                ),
+                (
+                    row("settings.show_fps", on_off(settings.show_fps)),
+                    MenuAction::ShowFps,
+                ),
+                (strings.get("back", &[]), MenuAction::Back),
+            ],
+        )
+    }
+
+    /// A row for each level showing its best score, then back.
+    fn levels_menu(&self) -> Menu {
+        let mut items: Vec<(String, MenuAction)> = self
+            .levels
+            .iter()
+            .enumerate()
+            .map(|(index, level)| {
+                let label = match self.high_scores.level_best(&level.name) {
+                    Some(best) => self.strings.get(
+                        "levels.entry",
+                        &[("name", &level.name), ("score", &best.to_string())],
+                    ),
+                    None => self
+                        .strings
+                        .get("levels.entry_new", &[("name", &level.name)]),
+                };
+                (label, MenuAction::Level(index))
+            })
+            .collect();
+        items.push((self.strings.get("back", &[]), MenuAction::Back));
+
+        Menu::new(MENU_LIST_Y as i32, items)
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.game_state == GameState::Playing
+    }
+
+    pub fn high_scores(&self) -> &HighScores {
+        &self.high_scores
+    }
+
+    /// Returns whether the high scores changed since the last call, so the host can save them.
+    /// Runs that used the console aren't recorded.
+    pub fn take_high_scores_changed(&mut self) -> bool {
+        std::mem::take(&mut self.high_scores_changed)
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// For settings the host changes itself, such as the display ones.
+    pub fn settings_mut(&mut self) -> &mut Settings {
+        &mut self.settings
+    }
+
+    /// Returns whether the settings scene changed the settings since the last call, so the
+    /// host can apply and save them.
+    pub fn take_settings_changed(&mut self) -> bool {
+        std::mem::take(&mut self.settings_changed)
+    }
+
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    pub fn tick_rate(&self) -> TickRate {
+        self.tick_rate
+    }
+
+    /// Returns the recording of the current (or most recently finished) run.
+    pub fn replay(&self) -> Replay {
+        Replay {
+            seed: self.seed,
+            tick_rate: self.tick_rate,
+            physics_preset: self.physics_preset,
+            ceiling_behaviour: self.ceiling.behaviour(),
+            collider_shape: self.collider_shape,
+            difficulty: self.difficulty,
+            progressive: self.progressive,
+            flaps: self.flaps.clone(),
+        }
+    }
+
+    /// The pipes for the current run, at the stage of the curve it has reached.
+    fn pipe_tuning(&self) -> PipeTuning {
+        self.difficulty.pipes_at(self.stage)
+    }
+
+    /// How fast the world scrolls, in pixels per second.
+    fn speed(&self) -> f32 {
+        match self.level {
+            Some(index) => self.levels[index].speed,
+            None => self.pipe_tuning().speed,
         }
     }
 
     fn check_for_new_pipe(&mut self) -> () {
         let last_pipe = self.pipes.last().unwrap();
 
-        if last_pipe.position.x as u32 + PIPE_WIDTH + PIPE_SPACING < WIDTH {
-            self.pipes.push(Pipe::new(&mut self.rng));
+        if last_pipe.position.x as u32 + PIPE_WIDTH + self.pipe_tuning().spacing < WIDTH {
+            self.spawn_pipe();
+        }
+    }
+
+    /// The pipes once the bird has passed `ahead` more of them.
+    /// Only passing pipes moves the score, so the stage it will be at is already known.
+    fn tuning_after(&self, ahead: usize) -> PipeTuning {
+        if !self.progressive || self.level.is_some() {
+            return PipeTuning {
+                speed: self.speed(),
+                ..self.pipe_tuning()
+            };
+        }
+        let stage = curve_stage(self.score.score + ahead as u32);
+        self.difficulty.pipes_at(stage)
+    }
+
+    /// Adds a pipe whose gap the bird can reach from the last pipe's.
+    /// The gap is placed where the witness flight can fly on to, at the speeds the
+    /// stages will have by the time the bird gets there, and within the envelope where
+    /// that leaves any room.
+    fn spawn_pipe(&mut self) -> () {
+        // The console can add pipes to levels too, they move at the level's pace.
+        let tuning = PipeTuning {
+            speed: self.speed(),
+            ..self.pipe_tuning()
+        };
+        let Some(last) = self.pipes.last() else {
+            // The first gap stays still, the bird can come in to it from anywhere.
+            let pipe = Pipe::with_behaviour(
+                &mut self.rng,
+                tuning,
+                PipeBehaviour::Static,
+                f32::NEG_INFINITY..f32::INFINITY,
+            );
+            let distance = WIDTH as f32 - self.bird.position.x - BIRD_WIDTH as f32;
+            self.witness = Witness::touching(pipe.placed_gap(), distance / tuning.speed);
+            self.pipes.push(pipe);
+            return;
+        };
+
+        // The bird is level with a pipe from touching it to scoring it.
+        let overlap = (PIPE_WIDTH + BIRD_WIDTH) as f32;
+        let ahead = self.pipes.iter().filter(|pipe| !pipe.passed).count();
+        let coming = self.tuning_after(ahead);
+        let speed = coming.speed;
+        let cleared = overlap / self.tuning_after(ahead.saturating_sub(1)).speed;
+        let reached = cleared + (WIDTH as f32 - last.position.x - overlap) / speed;
+        let physics = *self.bird.physics();
+        let envelope = Envelope::new(&physics, PipeTuning { speed, ..tuning });
+
+        // A gap that can't be flown to while it moves stays still instead, and when the
+        // flight can't fly on holding its height it tries holding any other through the
+        // last gap.
+        let picked = PipeBehaviour::pick(&mut self.rng, &PIPE_SPAWN_TABLE);
+        let free = self.witness.holding_any();
+        for (witness, behaviour) in [
+            (self.witness, picked),
+            (self.witness, PipeBehaviour::Static),
+            (free, picked),
+            (free, PipeBehaviour::Static),
+        ] {
+            let leg = Leg {
+                last: (last.placed_gap(), last.behaviour()),
+                cleared,
+                reached,
+                left: reached + overlap / speed,
+                size: tuning.gap_size as f32,
+                behaviour,
+                younger: last.age(),
+            };
+            let flights = witness.flights(&physics, &leg);
+            let (lowest, highest) = Pipe::tops(tuning, behaviour);
+            // The first flight through each top the gap can have.
+            let mut through = vec![None; (highest - lowest) as usize];
+            for (index, (tops, _)) in flights.iter().enumerate() {
+                let start = tops.start.ceil().max(lowest as f32) as u32;
+                let end = tops.end.floor().min((highest - 1) as f32) as u32;
+                for top in start..=end {
+                    through[(top - lowest) as usize].get_or_insert(index);
+                }
+            }
+            let comfortable = envelope.tops_after(
+                last.placed_gap(),
+                last.behaviour().reach(),
+                tuning.gap_size,
+                behaviour.reach(),
+            );
+            let flown = |top: &u32| through[(top - lowest) as usize].is_some();
+            let mut tops: Vec<u32> = (lowest..highest)
+                .filter(|top| flown(top) && comfortable.contains(&(*top as f32)))
+                .collect();
+            if tops.is_empty() {
+                tops = (lowest..highest).filter(flown).collect();
+            }
+            if tops.is_empty() {
+                continue;
+            }
+
+            // Flights that can't fly on to any gap after the new one are only taken when
+            // none of the first few through the tops tried can.
+            let gap = |top: u32| Gap {
+                top: top as f32,
+                size: tuning.gap_size as f32,
+            };
+            let after = self.tuning_after(ahead + 1);
+            let next = |top: u32| Leg {
+                last: (gap(top), behaviour),
+                cleared: overlap / speed,
+                reached: overlap / speed + (tuning.spacing - BIRD_WIDTH) as f32 / after.speed,
+                left: overlap / speed + (tuning.spacing + PIPE_WIDTH) as f32 / after.speed,
+                // The gap can't shrink any more before the next pipe spawns.
+                size: coming.gap_size as f32,
+                behaviour: PipeBehaviour::Static,
+                younger: (tuning.spacing + PIPE_WIDTH) as f32 / speed,
+            };
+            let first = tops[self.rng.random_range(0..tops.len())];
+            let mut placed = (
+                first,
+                flights[through[(first - lowest) as usize].unwrap()].1,
+            );
+            let (mut top, mut checked) = (first, 0);
+            'tops: loop {
+                let through = flights
+                    .iter()
+                    .filter(|(tops, _)| tops.start <= top as f32 && top as f32 <= tops.end);
+                for &(_, witness) in through {
+                    if checked == WITNESS_LOOKAHEAD {
+                        break 'tops;
+                    }
+                    checked += 1;
+                    if witness.flies_on(&physics, &next(top)) {
+                        placed = (top, witness);
+                        break 'tops;
+                    }
+                }
+                tops.retain(|&other| other != top);
+                if tops.is_empty() {
+                    break;
+                }
+                top = tops[self.rng.random_range(0..tops.len())];
+            }
+
+            let (top, witness) = placed;
+            self.witness = witness;
+            self.pipes.push(Pipe::placed(
+                WIDTH as f32,
+                tuning.speed,
+                gap(top),
+                behaviour,
+            ));
+            return;
+        }
+
+        // Only pipes added from the console can come too soon after the last one to fly
+        // to, they start a fresh flight.
+        let (lowest, highest) = Pipe::tops(tuning, PipeBehaviour::Static);
+        let pipe = Pipe::with_behaviour(
+            &mut self.rng,
+            tuning,
+            PipeBehaviour::Static,
+            lowest as f32..highest as f32,
+        );
+        let age = self.witness.age() + reached - last.age();
+        self.witness = Witness::touching(pipe.placed_gap(), age);
+        self.pipes.push(pipe);
+    }
+
+    /// Adds the level's pipes, coins and finish line that have scrolled onto the screen.
+    /// They come in at the right edge, already moved on by however far they are past it.
+    fn spawn_from_level(&mut self) -> () {
+        let Some(index) = self.level else {
+            return;
+        };
+        let level = &self.levels[index];
+        let screen_x = |x: f32| WIDTH as f32 + x - self.level_distance;
+
+        for pipe in &level.pipes[self.level_pipes_spawned..] {
+            if screen_x(pipe.x) > WIDTH as f32 {
+                break;
+            }
+            self.pipes.push(Pipe::placed(
+                screen_x(pipe.x),
+                level.speed,
+                pipe.gap,
+                pipe.behaviour,
+            ));
+            self.level_pipes_spawned += 1;
+        }
+
+        // Coins stay until the run ends, so every one spawned is still in the list.
+        for coin in &level.coins[self.coins.len()..] {
+            if screen_x(coin.x) > WIDTH as f32 {
+                break;
+            }
+            let position = Vector2::new(screen_x(coin.x), coin.y);
+            self.coins.push(Coin::new(position, level.speed));
+        }
+
+        if self.finish_line.is_none() && screen_x(level.finish) <= WIDTH as f32 {
+            self.finish_line = Some(FinishLine::new(screen_x(level.finish), level.speed));
+        }
+    }
+
+    /// Moves along the progressive curve once the score reaches the next stage.
+    /// Pipes already on screen speed up with the new ones so they keep their spacing.
+    fn check_for_new_stage(&mut self) -> () {
+        // Levels keep the pace they were made with.
+        if !self.progressive || self.level.is_some() {
+            return;
+        }
+
+        let stage = curve_stage(self.score.score);
+        if stage != self.stage {
+            self.stage = stage;
+            let speed = self.pipe_tuning().speed;
+            for pipe in &mut self.pipes {
+                pipe.set_speed(speed);
+            }
         }
     }
 
@@ -130,9 +1209,26 @@ impl Game {
         }
     }
 
+    /// Adds the points of the coins the bird flies through.
+    fn check_if_bird_collects_coins(&mut self) -> () {
+        for coin in &mut self.coins {
+            if !coin.collected && self.bird.collides_with(&coin.collision_box()) {
+                coin.collected = true;
+                self.score.add(COIN_POINTS);
+            }
+        }
+    }
+
+    /// Returns whether the bird has crossed the level's finish line.
+    fn check_if_bird_finished(&self) -> bool {
+        self.finish_line
+            .as_ref()
+            .is_some_and(|finish_line| self.bird.position.x > finish_line.x)
+    }
+
     /// Returns whether the bird should die.
-    /// The bird dies if it is in contact with any of the pipes
-    /// or if it is in contact with the ceiling or floor.
+    /// The bird dies if it is in contact with any of the pipes, the ground,
+    /// or a ceiling that kills.
     fn check_if_bird_dies(&self) -> bool {
         for pipe in &self.pipes {
             if self.bird.collides_with_pipe(pipe) {
@@ -140,53 +1236,698 @@ impl Game {
             }
         }
 
-        // Purposely not adding BIRD_HEIGHT for some leeway.
-        if self.bird.position.y < 0.0 || self.bird.position.y > HEIGHT as f32 {
-            return true;
-        }
+        let ground = &self.ground.collision_box;
+        self.bird.swept_into(ground, ground) || self.ceiling.kills(&self.bird)
+    }
 
-        false
+    /// Lands the bird on the ground if it has reached it.
+    fn keep_bird_above_ground(&mut self) -> () {
+        let ground = &self.ground.collision_box;
+        if self.bird.swept_into(ground, ground) {
+            self.bird.rest_on(self.ground.top());
+        }
     }
 
     /// Removes pipes that aren't in the screen anymore.
     fn clean_up_past_pipes(&mut self) -> () {
-        let first_pipe = self.pipes.first().unwrap();
+        // Levels can run out of pipes before the finish.
+        let Some(first_pipe) = self.pipes.first() else {
+            return;
+        };
 
         if (first_pipe.position.x + PIPE_WIDTH as f32) < 0.0 {
             self.pipes.remove(0);
         }
     }
 
-    fn draw_playing(&self, frame: &mut [u8]) -> () {
-        self.bird.draw(frame);
+    fn draw_playing(&self, frame: &mut [u8], alpha: f32) -> () {
+        self.draw_world(frame, alpha);
+
+        // Draw score last so that it draws over everything.
+        let best_score = match self.level {
+            Some(index) => self
+                .high_scores
+                .level_best(&self.levels[index].name)
+                .unwrap_or(0),
+            None => self.high_scores.best(),
+        };
+        let best = (best_score > 0).then(|| {
+            self.strings
+                .get("hud.best", &[("score", &best_score.to_string())])
+        });
+        let stage = (self.progressive && self.level.is_none()).then(|| {
+            self.strings
+                .get("hud.stage", &[("stage", &(self.stage + 1).to_string())])
+        });
+        self.score.draw(
+            frame,
+            self.score_position,
+            best.as_deref(),
+            stage.as_deref(),
+            &self.palette(),
+        );
+    }
+
+    fn palette(&self) -> Palette {
+        self.settings.theme.palette()
+    }
+
+    /// Draws the pipes, coins, finish line, ground and bird.
+    fn draw_world(&self, frame: &mut [u8], alpha: f32) -> () {
+        if let Some(finish_line) = &self.finish_line {
+            finish_line.draw(frame, alpha);
+        }
 
         for pipe in &self.pipes {
-            pipe.draw(frame);
+            pipe.draw(frame, alpha);
         }
 
-        // Draw score last so that it draws over everything.
-        self.score.draw(frame);
+        for coin in &self.coins {
+            coin.draw(frame, alpha);
+        }
+
+        self.ground.draw(frame, alpha);
+
+        // The bird goes over the ground so it can be seen lying on it.
+        self.bird.draw(frame, alpha);
+    }
+
+    /// Outlines the shapes used for collisions, at their latest simulated positions.
+    /// Also shows each pipe's passed flag, the bird's velocity and timing stats.
+    fn draw_debug_overlay(&self, frame: &mut [u8]) -> () {
+        for pipe in &self.pipes {
+            let [upper, lower] = pipe.collision_boxes();
+            Collider::Aabb(upper).draw_outline(frame, DEBUG_COLOR);
+            Collider::Aabb(lower).draw_outline(frame, DEBUG_COLOR);
+
+            // Label the top of the lower half, if the whole label fits on screen.
+            let label = if pipe.passed { "passed" } else { "ahead" };
+            let x = lower.min.x;
+            let (label_width, _) =
+                measure_text(label, &TextStyle::new().with_scale(DEBUG_FONT_PIXEL_SIZE));
+            if x >= 0.0 && x as u32 + label_width < WIDTH {
+                draw_string_sized(
+                    frame,
+                    label,
+                    x as u32,
+                    lower.min.y as u32 + DEBUG_FONT_PIXEL_SIZE,
+                    DEBUG_FONT_PIXEL_SIZE,
+                );
+            }
+        }
+        Collider::Aabb(self.ground.collision_box).draw_outline(frame, DEBUG_COLOR);
+
+        let bird_box = self.bird.collision_box();
+        Collider::Aabb(bird_box).draw_outline(frame, DEBUG_COLOR);
+        self.bird.collider().draw_outline(frame, DEBUG_COLOR);
+        let center = bird_box.min.lerp(bird_box.max, 0.5);
+        draw_line(
+            frame,
+            center,
+            center + self.bird.velocity() * DEBUG_VELOCITY_SECONDS,
+            DEBUG_COLOR,
+        );
+
+        let stats = [
+            format!("tick {}", self.tick),
+            format!("updates {}", self.frame_stats.updates),
+            format!(
+                "frame {:.1}ms",
+                self.frame_stats.frame_time.as_secs_f32() * 1000.0
+            ),
+            format!("pipes {}", self.pipes.len()),
+        ];
+        // Top right, clear of the score.
+        let stats_style = TextStyle::new()
+            .with_scale(DEBUG_FONT_PIXEL_SIZE)
+            .with_align(Align::Right)
+            .with_shadow(DEBUG_SHADOW_COLOR);
+        draw_text(
+            frame,
+            &stats.join("\n"),
+            (WIDTH - FONT_START_OFFSET) as i32,
+            DEBUG_STATS_Y as i32,
+            &stats_style,
+        );
+    }
+
+    /// Draws the frame rate in the top right corner.
+    fn draw_fps(&self, frame: &mut [u8]) -> () {
+        let frame_time = self.frame_stats.frame_time.as_secs_f32();
+        let fps = if frame_time > 0.0 {
+            1.0 / frame_time
+        } else {
+            0.0
+        };
+        let palette = self.palette();
+        let style = TextStyle::new()
+            .with_scale(UI_HINT_FONT_PIXEL_SIZE)
+            .with_palette(&palette)
+            .with_color(palette.hint)
+            .with_align(Align::Right);
+
+        draw_text(
+            frame,
+            &format!("{fps:.0} fps"),
+            (WIDTH - FONT_START_OFFSET) as i32,
+            FPS_COUNTER_Y as i32,
+            &style,
+        );
     }
 
     fn draw_main_menu(&self, frame: &mut [u8]) -> () {
-        draw_string(
+        self.ground.draw(frame, 1.0);
+        draw_title(frame, &self.strings.get("menu.title", &[]), &self.palette());
+    }
+
+    fn draw_dead(&self, frame: &mut [u8], alpha: f32) -> () {
+        self.draw_end_of_run(frame, alpha, "dead.score");
+    }
+
+    fn draw_level_complete(&self, frame: &mut [u8], alpha: f32) -> () {
+        self.draw_end_of_run(frame, alpha, "complete.score");
+    }
+
+    /// Draws the final scene behind the menu, with the message saying how the run went.
+    fn draw_end_of_run(&self, frame: &mut [u8], alpha: f32, message: &str) -> () {
+        self.draw_world(frame, alpha);
+
+        let style = TextStyle::new()
+            .with_palette(&self.palette())
+            .with_align(Align::Centre)
+            .with_wrap_width(WIDTH - 2 * FONT_START_OFFSET);
+        draw_text(
             frame,
-            "want to play? (y/n)",
-            FONT_START_OFFSET,
-            FONT_START_OFFSET,
+            &self.strings.plural(message, self.score.score, &[]),
+            WIDTH as i32 / 2,
+            MENU_TITLE_Y as i32,
+            &style,
         );
     }
 
-    fn draw_dead(&self, frame: &mut [u8]) -> () {
-        let score_string = format!("you got a score of {}!", self.score.score);
-        draw_string(frame, &score_string, FONT_START_OFFSET, FONT_START_OFFSET);
+    fn draw_high_scores(&self, frame: &mut [u8]) -> () {
+        self.ground.draw(frame, 1.0);
+        let palette = self.palette();
+        draw_title(frame, &self.strings.get("high_scores.title", &[]), &palette);
+
+        let scores = self.high_scores.scores();
+        let lines: Vec<String> = if scores.is_empty() {
+            vec![self.strings.get("high_scores.empty", &[])]
+        } else {
+            scores
+                .iter()
+                .enumerate()
+                .map(|(i, score)| {
+                    self.strings.get(
+                        "high_scores.entry",
+                        &[
+                            ("rank", &(i + 1).to_string()),
+                            ("score", &score.to_string()),
+                        ],
+                    )
+                })
+                .collect()
+        };
+        draw_list(frame, &lines.join("\n"), &palette);
+    }
 
-        let play_again_string = format!("play again? (y/n)");
-        draw_string(
+    fn draw_levels(&self, frame: &mut [u8]) -> () {
+        self.ground.draw(frame, 1.0);
+        draw_title(
             frame,
-            &play_again_string,
-            FONT_START_OFFSET,
-            FONT_START_OFFSET + FONT_HEIGHT_PIXEL_MAP_PIXELS * FONT_PIXEL_SIZE_SCREEN_PIXELS,
+            &self.strings.get("levels.title", &[]),
+            &self.palette(),
+        );
+    }
+
+    fn draw_settings(&self, frame: &mut [u8]) -> () {
+        self.ground.draw(frame, 1.0);
+        draw_title(
+            frame,
+            &self.strings.get("settings.title", &[]),
+            &self.palette(),
+        );
+    }
+}
+
+/// Returns the value `step` places after the current one in the options, wrapping around.
+fn cycle<T: Copy + PartialEq>(options: &[T], current: T, step: i32) -> T {
+    let position = options
+        .iter()
+        .position(|&option| option == current)
+        .unwrap_or(0);
+    let len = options.len() as i32;
+    options[(position as i32 + step).rem_euclid(len) as usize]
+}
+
+/// The seed of today's daily challenge, the same for everyone on the same UTC day.
+fn daily_seed() -> u64 {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    seconds / (24 * 60 * 60)
+}
+
+/// Draws a screen's title centred at the top.
+fn draw_title(frame: &mut [u8], title: &str, palette: &Palette) -> () {
+    let style = TextStyle::new()
+        .with_scale(MENU_TITLE_FONT_PIXEL_SIZE)
+        .with_palette(palette)
+        .with_align(Align::Centre);
+    draw_text(frame, title, WIDTH as i32 / 2, MENU_TITLE_Y as i32, &style);
+}
+
+/// Draws the lines of a screen's list centred under its title.
+fn draw_list(frame: &mut [u8], text: &str, palette: &Palette) -> () {
+    let style = TextStyle::new()
+        .with_scale(UI_HINT_FONT_PIXEL_SIZE)
+        .with_palette(palette)
+        .with_color(palette.hint)
+        .with_align(Align::Centre);
+    draw_text(frame, text, WIDTH as i32 / 2, MENU_LIST_Y as i32, &style);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Difficulty, Game, GameState, HighScores, Level, Outcome, PhysicsPreset, PipeBehaviour,
+        TickRate,
+    };
+    use crate::config::{
+        BIRD_HEIGHT, COIN_POINTS, COIN_SIZE, CONSOLE_MAX_FAST_FORWARD, DEFAULT_CONSOLE_KEY,
+        GROUND_Y, MENU_TOP_Y, PIPE_SPEED, WIDTH,
+    };
+    use crate::game::collision_box::CollisionBox;
+    use crate::game::pipe::Pipe;
+    use crate::game::pipe_behaviour::Gap;
+    use crate::settings::Settings;
+
+    const TICK_RATES: [TickRate; 3] = [TickRate::Hz60, TickRate::Hz120, TickRate::Hz240];
+
+    /// Plays a run at the given tick rate, flapping every `flap_interval` seconds.
+    /// Returns the bird's height sampled every `sample_interval` seconds and the
+    /// time of death.
+    fn simulate(
+        tick_rate: TickRate,
+        seed: u64,
+        flap_interval: f32,
+        sample_interval: f32,
+    ) -> (Vec<f32>, f32) {
+        let hz = tick_rate.hz() as f32;
+        let flap_ticks = (flap_interval * hz).round() as u64;
+        let sample_ticks = (sample_interval * hz).round() as u64;
+
+        let mut game = Game::with_seed(seed).with_tick_rate(tick_rate);
+        game.start_run();
+
+        let mut samples = Vec::new();
+        while game.is_playing() {
+            if game.tick().is_multiple_of(sample_ticks) {
+                samples.push(game.bird.position.y);
+            }
+            if game.tick().is_multiple_of(flap_ticks) {
+                game.space_bar_hit();
+            }
+            game.update(tick_rate.dt());
+        }
+
+        (samples, game.tick() as f32 / hz)
+    }
+
+    #[test]
+    fn trajectories_agree_across_tick_rates() {
+        // Flap intervals are whole 60Hz ticks so every rate flaps at the same instants.
+        let (reference, _) = simulate(TickRate::Hz120, 3, 1.25, 0.25);
+        assert!(!reference.is_empty());
+
+        for tick_rate in TICK_RATES
+            .into_iter()
+            .filter(|&rate| rate != TickRate::Hz120)
+        {
+            let (samples, _) = simulate(tick_rate, 3, 1.25, 0.25);
+
+            assert_eq!(samples.len(), reference.len(), "{tick_rate}Hz");
+            for (time, (y, expected)) in samples.iter().zip(&reference).enumerate() {
+                assert!(
+                    (y - expected).abs() < 0.5,
+                    "{tick_rate}Hz: bird at {y} instead of {expected} after {}s",
+                    time as f32 * 0.25
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn death_tick_agrees_across_tick_rates() {
+        for seed in 0..10 {
+            let (_, reference) = simulate(TickRate::Hz120, seed, 5.0 / 3.0, 1.0);
+
+            for tick_rate in TICK_RATES
+                .into_iter()
+                .filter(|&rate| rate != TickRate::Hz120)
+            {
+                let (_, death_time) = simulate(tick_rate, seed, 5.0 / 3.0, 1.0);
+
+                // Death is only checked once per tick, so allow one 60Hz tick either way.
+                assert!(
+                    (death_time - reference).abs() <= 1.0 / 60.0 + f32::EPSILON,
+                    "seed {seed}: died after {death_time}s at {tick_rate}Hz, {reference}s at 120Hz"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn bird_rests_on_the_ground_after_dying() {
+        let mut game = Game::with_seed(0);
+        game.start_run();
+        while game.is_playing() {
+            game.update(TickRate::Hz120.dt());
+        }
+
+        for _ in 0..240 {
+            game.update(TickRate::Hz120.dt());
+        }
+
+        assert_eq!(game.bird.position.y, (GROUND_Y - BIRD_HEIGHT) as f32);
+    }
+
+    #[test]
+    fn bird_cannot_fall_through_the_ground_at_ten_times_speed() {
+        // At terminal velocity the heavy bird moves further than the ground is thick each step.
+        let dt = 10.0 * TickRate::Hz60.dt();
+
+        for seed in 0..10 {
+            let mut game = Game::with_seed(seed).with_physics_preset(PhysicsPreset::Heavy);
+            game.start_run();
+            while game.is_playing() && game.tick() < 1000 {
+                game.update(dt);
+            }
+
+            assert!(
+                game.game_state == GameState::Dead,
+                "seed {seed}: still alive"
+            );
+            assert_eq!(game.bird.position.y, (GROUND_Y - BIRD_HEIGHT) as f32);
+        }
+    }
+
+    #[test]
+    fn collision_boxes_follow_positions() {
+        let dt = 0.25;
+        let mut game = Game::with_seed(0);
+        game.start_run();
+        let placed = Gap {
+            top: 150.0,
+            size: 125.0,
+        };
+        let oscillating = PipeBehaviour::Oscillating {
+            amplitude: 40.0,
+            period: 4.0,
+        };
+        game.pipes = vec![
+            Pipe::placed(700.0, PIPE_SPEED, placed, PipeBehaviour::Static),
+            Pipe::placed(800.0, PIPE_SPEED, placed, oscillating),
+        ];
+
+        // Falls from rest for half a second, flaps, then climbs for another half.
+        for tick in 0..4 {
+            if tick == 2 {
+                game.space_bar_hit();
+            }
+            game.update(dt);
+        }
+
+        let corners = |b: CollisionBox| ((b.min.x, b.min.y), (b.max.x, b.max.y));
+        // 7.5 and 22.5 down to 230, then 42.5 and 27.5 back up.
+        assert_eq!(
+            corners(game.bird.collision_box()),
+            ((300.0, 160.0), (330.0, 190.0))
         );
+        // A second at 80 pixels per second.
+        let [upper, lower] = game.pipes[0].collision_boxes();
+        assert_eq!(corners(upper), ((620.0, 0.0), (650.0, 150.0)));
+        assert_eq!(corners(lower), ((620.0, 275.0), (650.0, 560.0)));
+        // A quarter of the period in, the gap is at the bottom of its swing.
+        let [upper, lower] = game.pipes[1].collision_boxes();
+        assert_eq!(corners(upper), ((720.0, 0.0), (750.0, 190.0)));
+        assert_eq!(corners(lower), ((720.0, 315.0), (750.0, 560.0)));
+    }
+
+    /// A million ticks of hovering runs, too slow for every test run:
+    /// `cargo test --release -- --ignored over_a_long_soak`.
+    #[test]
+    #[ignore]
+    fn collision_boxes_follow_positions_over_a_long_soak() {
+        let dt = TickRate::Hz120.dt();
+        let mut game = Game::with_seed(0);
+        game.start_run();
+
+        for tick in 0..1_000_000_u64 {
+            if game.bird.position.y > 250.0 {
+                game.space_bar_hit();
+            }
+            game.update(dt);
+
+            // Boxes are derived from the positions that get drawn, so they never drift.
+            assert_eq!(game.bird.collision_box().min, game.bird.position);
+            for pipe in &game.pipes {
+                let [upper, lower] = pipe.collision_boxes();
+                assert_eq!(upper.min, pipe.position);
+                assert!(upper.max.x == lower.max.x && lower.min.x == pipe.position.x);
+            }
+
+            if game.game_state == GameState::Dead {
+                game = Game::with_seed(tick);
+                game.start_run();
+            }
+        }
+    }
+
+    #[test]
+    fn console_god_mode_survives_a_fast_forward() {
+        let mut game = Game::with_seed(5);
+        game.start_run();
+
+        for line in ["god on", "tick 6000"] {
+            game.console().type_text(line);
+            game.submit_console();
+        }
+
+        assert!(game.is_playing());
+        assert_eq!(game.tick(), 6000);
+    }
+
+    #[test]
+    fn console_fast_forward_is_capped() {
+        let mut game = Game::with_seed(5);
+        game.start_run();
+
+        for line in ["god on", "tick 99999999999"] {
+            game.console().type_text(line);
+            game.submit_console();
+        }
+
+        assert!(game.is_playing());
+        assert_eq!(game.tick(), CONSOLE_MAX_FAST_FORWARD);
+    }
+
+    #[test]
+    fn progressive_runs_speed_up_every_pipe_at_a_new_stage() {
+        let mut game = Game::with_seed(3).with_progressive(true);
+        let dt = game.tick_rate().dt();
+        game.start_run();
+        for line in ["god on", "tick 600", "score 25"] {
+            game.console().type_text(line);
+            game.submit_console();
+        }
+        assert_eq!(game.stage, 0);
+
+        game.update(dt);
+        assert_eq!(game.stage, 2);
+        let speed = Difficulty::Normal.pipes_at(2).speed;
+        let before: Vec<f32> = game.pipes.iter().map(|pipe| pipe.position.x).collect();
+        game.update(dt);
+        for (pipe, x) in game.pipes.iter().zip(before) {
+            assert!((x - pipe.position.x - speed * dt).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn only_runs_without_the_console_count_for_high_scores() {
+        let mut high_scores = HighScores::default();
+        high_scores.record(3);
+        let mut game = Game::with_seed(0).with_high_scores(high_scores);
+        game.start_run();
+        game.console().type_text("score 10");
+        game.submit_console();
+        while game.is_playing() {
+            game.update(TickRate::Hz120.dt());
+        }
+        assert!(!game.take_high_scores_changed());
+        assert_eq!(game.high_scores().best(), 3);
+
+        game.start_run();
+        game.score.score = 5;
+        while game.is_playing() {
+            game.update(TickRate::Hz120.dt());
+        }
+        assert!(game.take_high_scores_changed());
+        assert!(!game.take_high_scores_changed());
+        assert_eq!(game.high_scores().best(), 5);
+    }
+
+    #[test]
+    fn menus_start_runs_on_the_seed_once() {
+        let mut game = Game::with_seed(9);
+        game.down_key_hit();
+        game.down_key_hit();
+        game.enter_key_hit();
+        assert!(game.game_state == GameState::HighScores);
+        game.escape_key_hit();
+        assert!(game.game_state == GameState::MainMenu);
+
+        game.enter_key_hit();
+        assert!(game.is_playing());
+        assert_eq!(game.seed, 9);
+        while game.is_playing() {
+            game.update(TickRate::Hz120.dt());
+        }
+
+        // The death menu ignores input meant for the bird for a moment.
+        game.up_key_hit();
+        assert_eq!(game.enter_key_hit(), Outcome::Continue);
+        assert_eq!(
+            game.click(WIDTH as f32 / 2.0, MENU_TOP_Y as f32),
+            Outcome::Continue
+        );
+        assert!(game.game_state == GameState::Dead);
+        while game.menu_grace > 0.0 {
+            game.update(TickRate::Hz120.dt());
+        }
+
+        // Up from retry wraps to quit, and retry plays a new seed.
+        assert_eq!(game.enter_key_hit(), Outcome::Quit);
+        assert!(game.game_state == GameState::Dead);
+        game.down_key_hit();
+        assert_eq!(game.enter_key_hit(), Outcome::Continue);
+        assert!(game.is_playing());
+        assert_ne!(game.seed, 9);
+    }
+
+    #[test]
+    fn settings_scene_changes_and_rebinds() {
+        let mut game = Game::with_seed(0);
+        game.up_key_hit();
+        game.up_key_hit();
+        game.enter_key_hit();
+        assert!(game.game_state == GameState::Settings);
+
+        // Volume is the first row, it steps by ten and stops at either end.
+        game.left_key_hit();
+        assert_eq!(game.settings().volume, 90);
+        for _ in 0..3 {
+            game.right_key_hit();
+        }
+        assert_eq!(game.settings().volume, 100);
+        for _ in 0..12 {
+            game.left_key_hit();
+        }
+        assert_eq!(game.settings().volume, 0);
+        game.enter_key_hit();
+        assert_eq!(game.settings().volume, 10);
+        assert!(game.take_settings_changed());
+        let path = std::env::temp_dir().join(format!(
+            "rust-game-settings-volume-{}.cfg",
+            std::process::id()
+        ));
+        game.settings().save(&path).unwrap();
+        assert_eq!(Settings::load(&path).volume, 10);
+        std::fs::remove_file(&path).unwrap();
+
+        // The window size is the fourth row, difficulty the sixth and the curve the seventh.
+        game.down_key_hit();
+        game.down_key_hit();
+        game.down_key_hit();
+        game.left_key_hit();
+        assert_eq!(game.settings().display.window_scale, 75);
+        game.down_key_hit();
+        game.down_key_hit();
+        game.right_key_hit();
+        assert_eq!(game.settings().difficulty, Difficulty::Hard);
+        assert_eq!(game.difficulty, Difficulty::Hard);
+        game.down_key_hit();
+        game.enter_key_hit();
+        assert!(game.settings().progressive);
+        assert!(game.progressive);
+        game.up_key_hit();
+        assert!(game.take_settings_changed());
+        assert!(!game.take_settings_changed());
+
+        // Rebuilding the rows keeps the selection.
+        game.up_key_hit();
+        game.up_key_hit();
+        game.up_key_hit();
+        game.up_key_hit();
+        game.enter_key_hit();
+        assert!(game.is_rebinding());
+        game.bind_key(Some("w".to_string()));
+        assert!(!game.is_rebinding());
+        assert_eq!(game.settings().keys.flap, "w");
+
+        game.enter_key_hit();
+        game.escape_key_hit();
+        assert!(!game.is_rebinding());
+        assert!(game.game_state == GameState::Settings);
+        assert_eq!(game.settings().keys.flap, "w");
+
+        // The console can't share the flap key.
+        game.down_key_hit();
+        game.enter_key_hit();
+        game.bind_key(Some("w".to_string()));
+        assert!(game.is_rebinding());
+        assert_eq!(game.settings().keys.console, DEFAULT_CONSOLE_KEY);
+        game.bind_key(Some("c".to_string()));
+        assert!(!game.is_rebinding());
+        assert_eq!(game.settings().keys.console, "c");
+    }
+
+    #[test]
+    fn levels_end_at_the_finish_and_keep_their_best() {
+        // A coin where the bird lands, so it is collected on the way to the finish.
+        let level = Level::parse(&format!(
+            "name test\npipe 50 200 150\ncoin 10 {}\nfinish 100\n",
+            GROUND_Y - COIN_SIZE
+        ))
+        .unwrap();
+        let mut game = Game::with_seed(0).with_levels(vec![level]);
+        for _ in 0..3 {
+            game.down_key_hit();
+        }
+        game.enter_key_hit();
+        assert!(game.game_state == GameState::Levels);
+        game.enter_key_hit();
+        assert!(game.is_playing());
+
+        // Nothing kills the bird, but it still passes the pipe and collects the coin.
+        game.god_mode = true;
+        while game.is_playing() {
+            game.update(TickRate::Hz120.dt());
+        }
+        assert!(game.game_state == GameState::LevelComplete);
+        assert_eq!(game.score.score, 1 + COIN_POINTS);
+        assert!(game.take_high_scores_changed());
+        assert_eq!(game.high_scores().level_best("test"), Some(1 + COIN_POINTS));
+        assert_eq!(game.high_scores().best(), 0);
+
+        // Without a next level, the menu starts with retry.
+        while game.menu_grace > 0.0 {
+            game.update(TickRate::Hz120.dt());
+        }
+        game.enter_key_hit();
+        assert!(game.is_playing());
+        assert_eq!(game.level, Some(0));
+        assert_eq!(game.score.score, 0);
     }
 }
@@ -1,78 +1,180 @@
 use crate::config::{
-    BIRD_A_COLOR, BIRD_B_COLOR, BIRD_FLY_SPEED, BIRD_G_COLOR, BIRD_GRAVITY_ACCELERATION_SCALE,
-    BIRD_HEIGHT, BIRD_R_COLOR, BIRD_START_POSITION_X, BIRD_START_POSITION_Y, BIRD_WIDTH,
-    PIPE_WIDTH, WIDTH,
+    BIRD_CAPSULE_HALF_LENGTH, BIRD_CAPSULE_RADIUS, BIRD_HEIGHT, BIRD_START_POSITION_X,
+    BIRD_START_POSITION_Y, BIRD_WIDTH, PIPE_WIDTH,
 };
-use crate::game::collision_box::CollisionBox;
+use crate::game::bird_physics::BirdPhysics;
+use crate::game::collider::{Collider, ColliderShape, Mask};
+use crate::game::collision_box::{CollisionBox, Hitbox};
 use crate::game::pipe::Pipe;
+use crate::game::sprite::Sprite;
 use crate::game::vector2::Vector2;
 
 /// Struct for the pipe.
 /// A bird's position represents the top left corner of them.
 pub struct Bird {
     pub position: Vector2,
+    /// Position before the last update, used to interpolate rendering between updates.
+    previous_position: Vector2,
     velocity: Vector2,
-    collision_box: CollisionBox,
+    hitbox: Hitbox,
+    physics: BirdPhysics,
+    sprite: Sprite,
+    /// Solid pixels of the sprite, for the mask collider.
+    mask: Mask,
+    collider_shape: ColliderShape,
+    /// Seconds left before the bird can flap again.
+    flap_cooldown: f32,
 }
 
 impl Bird {
-    pub fn new() -> Self {
-        let min = Vector2::new(BIRD_START_POSITION_X as f32, BIRD_START_POSITION_Y as f32);
-        let max = Vector2::new(
-            (BIRD_START_POSITION_X + BIRD_WIDTH) as f32,
-            (BIRD_START_POSITION_Y + BIRD_HEIGHT) as f32,
-        );
+    pub fn new(physics: BirdPhysics, collider_shape: ColliderShape) -> Self {
+        let position = Vector2::new(BIRD_START_POSITION_X as f32, BIRD_START_POSITION_Y as f32);
+        let sprite = Sprite::bird();
 
         Self {
-            position: min,
+            position,
+            previous_position: position,
             velocity: Vector2::new(0.0, 0.0),
-            collision_box: CollisionBox::new(min, max),
+            hitbox: Hitbox::new(
+                Vector2::new(0.0, 0.0),
+                Vector2::new(BIRD_WIDTH as f32, BIRD_HEIGHT as f32),
+            ),
+            physics,
+            mask: Mask::from_alpha(&sprite),
+            sprite,
+            collider_shape,
+            flap_cooldown: 0.0,
         }
     }
 
     pub fn fly(&mut self) -> () {
-        self.velocity = Vector2::up() * BIRD_FLY_SPEED;
+        if self.flap_cooldown > 0.0 {
+            return;
+        }
+
+        self.velocity = Vector2::new(0.0, self.physics.flap(self.velocity.y));
+        self.flap_cooldown = self.physics.flap_cooldown;
     }
 
     pub fn update(&mut self, dt: f32) -> () {
-        self.velocity = self.velocity + Vector2::down() * BIRD_GRAVITY_ACCELERATION_SCALE;
-        self.position = self.position + self.velocity * dt;
+        self.previous_position = self.position;
+        self.flap_cooldown = (self.flap_cooldown - dt).max(0.0);
+
+        let (velocity, distance) = self.physics.step(self.velocity.y, dt);
+        self.velocity = Vector2::new(0.0, velocity);
+        self.position = self.position + Vector2::down() * distance;
+    }
+
+    /// Moves the bird down so its top is at `y`, reflecting any upwards speed scaled by
+    /// `restitution` (0 just stops it).
+    pub fn deflect_down(&mut self, y: f32, restitution: f32) -> () {
+        self.position = Vector2::new(self.position.x, y);
+        if self.velocity.y < 0.0 {
+            self.velocity = Vector2::new(0.0, -self.velocity.y * restitution);
+        }
+    }
+
+    /// Puts the bird at rest with its bottom at `y`.
+    pub fn rest_on(&mut self, y: f32) -> () {
+        self.position = Vector2::new(self.position.x, y - BIRD_HEIGHT as f32);
+        self.velocity = Vector2::new(0.0, 0.0);
+    }
+
+    /// Draws the bird `alpha` of the way from its previous position to its current one.
+    pub fn draw(&self, frame: &mut [u8], alpha: f32) -> () {
+        let position = self.previous_position.lerp(self.position, alpha);
+
+        self.sprite
+            .draw(frame, position.x as i32, position.y as i32);
+    }
 
-        // Update collision box
-        self.collision_box.min = self.collision_box.min + self.velocity * dt;
-        self.collision_box.max = self.collision_box.max + self.velocity * dt;
+    pub fn physics(&self) -> &BirdPhysics {
+        &self.physics
     }
 
-    pub fn draw(&self, frame: &mut [u8]) -> () {
-        let stride = WIDTH as usize * 4;
+    /// For live tweaking from the developer console.
+    pub fn physics_mut(&mut self) -> &mut BirdPhysics {
+        &mut self.physics
+    }
 
-        let x_position = self.position.x as usize;
-        let y_position = self.position.y as usize;
+    pub fn velocity(&self) -> Vector2 {
+        self.velocity
+    }
 
-        for x in x_position..x_position + BIRD_WIDTH as usize {
-            for y in y_position..y_position + BIRD_HEIGHT as usize {
-                let idx = y * stride + x as usize * 4;
+    /// Returns the shape the bird currently collides with.
+    pub fn collider(&self) -> Collider<'_> {
+        self.collider_at(1.0)
+    }
 
-                if idx > frame.len() {
-                    break;
-                }
+    /// Returns the shape the bird collided with `t` of the way through the last update.
+    fn collider_at(&self, t: f32) -> Collider<'_> {
+        let position = self.previous_position.lerp(self.position, t);
+        let center = position + Vector2::new(BIRD_WIDTH as f32, BIRD_HEIGHT as f32) * 0.5;
 
-                frame[idx + 0] = BIRD_R_COLOR;
-                frame[idx + 1] = BIRD_G_COLOR;
-                frame[idx + 2] = BIRD_B_COLOR;
-                frame[idx + 3] = BIRD_A_COLOR;
-            }
+        match self.collider_shape {
+            ColliderShape::Aabb => Collider::Aabb(self.hitbox.at(position)),
+            ColliderShape::Circle => Collider::Circle {
+                center,
+                radius: BIRD_WIDTH.min(BIRD_HEIGHT) as f32 / 2.0,
+            },
+            ColliderShape::Capsule => Collider::Capsule {
+                start: center + Vector2::left() * BIRD_CAPSULE_HALF_LENGTH,
+                end: center + Vector2::left() * -BIRD_CAPSULE_HALF_LENGTH,
+                radius: BIRD_CAPSULE_RADIUS,
+            },
+            ColliderShape::Mask => Collider::Mask {
+                origin: position,
+                mask: &self.mask,
+            },
         }
     }
 
+    /// The bird's box, derived from its position.
+    pub fn collision_box(&self) -> CollisionBox {
+        self.hitbox.at(self.position)
+    }
+
+    /// Returns whether the bird touched a box, moving from `previous` to `current`, at any
+    /// point during the last update rather than only at its end.
+    pub fn swept_into(&self, previous: &CollisionBox, current: &CollisionBox) -> bool {
+        let Some(time) = CollisionBox::time_of_impact(
+            &self.hitbox.at(self.previous_position),
+            &self.collision_box(),
+            previous,
+            current,
+        ) else {
+            return false;
+        };
+
+        // The box only bounds the bird's shape, so check the shape itself from the impact on,
+        // with neither of the other box's edges moving more than a pixel between checks.
+        let bird_motion = self.position - self.previous_position;
+        let motion = (bird_motion - (current.min - previous.min))
+            .length_squared()
+            .max((bird_motion - (current.max - previous.max)).length_squared());
+        let steps = (motion.sqrt() * (1.0 - time)).ceil().max(1.0) as u32;
+        (0..=steps).any(|step| {
+            let t = time + (1.0 - time) * step as f32 / steps as f32;
+            self.collider_at(t)
+                .collides_with_box(&previous.lerp(current, t))
+        })
+    }
+
     /// Returns whether this bird is past the given pipe.
     pub fn is_passed_pipe(&self, pipe: &Pipe) -> bool {
         self.position.x > pipe.position.x + PIPE_WIDTH as f32
     }
 
     /// Returns whether this bird collides with the given pipe.
+    /// Checks the whole of the last update, so neither can skip through the other.
     pub fn collides_with_pipe(&self, pipe: &Pipe) -> bool {
-        self.collision_box.collides_with(&pipe.upper_collision_box)
-            || self.collision_box.collides_with(&pipe.lower_collision_box)
+        pipe.previous_collision_boxes()
+            .iter()
+            .zip(pipe.collision_boxes())
+            .any(|(previous, current)| self.swept_into(previous, &current))
+    }
+
+    pub fn collides_with(&self, collision_box: &CollisionBox) -> bool {
+        self.collider().collides_with_box(collision_box)
     }
 }
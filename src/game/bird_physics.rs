@@ -0,0 +1,182 @@
+use crate::config::{BIRD_FLY_SPEED, BIRD_GRAVITY_ACCELERATION, BIRD_TERMINAL_VELOCITY};
+use std::fmt;
+use std::str::FromStr;
+
+/// What a flap does to the bird's vertical velocity.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FlapMode {
+    /// Replace the velocity with `flap_speed` upwards, the classic feel.
+    SetVelocity,
+    /// Add `flap_speed` upwards to the current velocity, so a flap while falling fast
+    /// only slows the fall.
+    AddImpulse,
+}
+
+/// Tunables for how the bird moves. All values are per second so they don't depend on
+/// the tick rate.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BirdPhysics {
+    /// Downwards acceleration in pixels per second squared.
+    pub gravity: f32,
+    /// Upwards speed given by a flap in pixels per second.
+    pub flap_speed: f32,
+    pub flap_mode: FlapMode,
+    /// Maximum falling speed in pixels per second, None for unbounded.
+    pub terminal_velocity: Option<f32>,
+    /// Linear drag coefficient per second, 0 disables drag.
+    pub drag: f32,
+    /// Seconds after a flap during which further flaps are ignored.
+    pub flap_cooldown: f32,
+}
+
+impl BirdPhysics {
+    /// Returns the new vertical velocity and the distance travelled after `dt` seconds,
+    /// starting at `velocity`.
+    /// Gravity and drag are integrated exactly so the trajectory doesn't depend on dt.
+    pub fn step(&self, velocity: f32, dt: f32) -> (f32, f32) {
+        let (new_velocity, displacement) = if self.drag > 0.0 {
+            // With linear drag the velocity decays exponentially towards g / k.
+            let drag_limit = self.gravity / self.drag;
+            let decay = (-self.drag * dt).exp();
+            (
+                drag_limit + (velocity - drag_limit) * decay,
+                drag_limit * dt + (velocity - drag_limit) * (1.0 - decay) / self.drag,
+            )
+        } else {
+            (
+                velocity + self.gravity * dt,
+                velocity * dt + 0.5 * self.gravity * dt * dt,
+            )
+        };
+
+        match self.terminal_velocity {
+            Some(terminal) if new_velocity > terminal => {
+                (terminal, displacement.min(terminal * dt))
+            }
+            _ => (new_velocity, displacement),
+        }
+    }
+
+    /// Returns the vertical velocity right after a flap.
+    pub fn flap(&self, velocity: f32) -> f32 {
+        match self.flap_mode {
+            FlapMode::SetVelocity => -self.flap_speed,
+            FlapMode::AddImpulse => velocity - self.flap_speed,
+        }
+    }
+}
+
+/// Named physics tunings selectable from the settings file.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PhysicsPreset {
+    Classic,
+    /// Low gravity and drag, the bird drifts.
+    Floaty,
+    /// Strong gravity with impulse flaps and a short cooldown.
+    Heavy,
+}
+
+impl PhysicsPreset {
+    pub fn physics(self) -> BirdPhysics {
+        match self {
+            PhysicsPreset::Classic => BirdPhysics {
+                gravity: BIRD_GRAVITY_ACCELERATION,
+                flap_speed: BIRD_FLY_SPEED,
+                flap_mode: FlapMode::SetVelocity,
+                terminal_velocity: Some(BIRD_TERMINAL_VELOCITY),
+                drag: 0.0,
+                flap_cooldown: 0.0,
+            },
+            PhysicsPreset::Floaty => BirdPhysics {
+                gravity: 160.0,
+                flap_speed: 150.0,
+                flap_mode: FlapMode::SetVelocity,
+                terminal_velocity: Some(200.0),
+                drag: 0.8,
+                flap_cooldown: 0.0,
+            },
+            PhysicsPreset::Heavy => BirdPhysics {
+                gravity: 480.0,
+                flap_speed: 300.0,
+                flap_mode: FlapMode::AddImpulse,
+                terminal_velocity: Some(600.0),
+                drag: 0.0,
+                flap_cooldown: 0.15,
+            },
+        }
+    }
+}
+
+impl FromStr for PhysicsPreset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "classic" => Ok(PhysicsPreset::Classic),
+            "floaty" => Ok(PhysicsPreset::Floaty),
+            "heavy" => Ok(PhysicsPreset::Heavy),
+            _ => Err(format!(
+                "unknown physics preset `{s}`, expected classic, floaty or heavy"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for PhysicsPreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            PhysicsPreset::Classic => "classic",
+            PhysicsPreset::Floaty => "floaty",
+            PhysicsPreset::Heavy => "heavy",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FlapMode, PhysicsPreset};
+
+    /// Falls for `seconds` from rest in steps of `dt`, returning velocity and distance.
+    fn fall(preset: PhysicsPreset, seconds: f32, dt: f32) -> (f32, f32) {
+        let physics = preset.physics();
+        let (mut velocity, mut distance) = (0.0, 0.0);
+        for _ in 0..(seconds / dt).round() as u32 {
+            let (new_velocity, displacement) = physics.step(velocity, dt);
+            velocity = new_velocity;
+            distance += displacement;
+        }
+        (velocity, distance)
+    }
+
+    #[test]
+    fn falling_speed_is_capped_at_terminal_velocity() {
+        for preset in [
+            PhysicsPreset::Classic,
+            PhysicsPreset::Floaty,
+            PhysicsPreset::Heavy,
+        ] {
+            let terminal = preset.physics().terminal_velocity.unwrap();
+            let (velocity, _) = fall(preset, 10.0, 1.0 / 120.0);
+
+            assert!(velocity <= terminal, "{preset}: {velocity} > {terminal}");
+        }
+    }
+
+    #[test]
+    fn drag_is_integrated_independently_of_dt() {
+        let (_, coarse) = fall(PhysicsPreset::Floaty, 0.5, 1.0 / 60.0);
+        let (_, fine) = fall(PhysicsPreset::Floaty, 0.5, 1.0 / 240.0);
+
+        assert!((coarse - fine).abs() < 0.01, "{coarse} vs {fine}");
+    }
+
+    #[test]
+    fn flap_modes() {
+        let mut physics = PhysicsPreset::Classic.physics();
+        assert_eq!(physics.flap(300.0), -physics.flap_speed);
+
+        physics.flap_mode = FlapMode::AddImpulse;
+        assert_eq!(physics.flap(300.0), 300.0 - physics.flap_speed);
+    }
+}
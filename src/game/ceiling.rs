@@ -0,0 +1,132 @@
+use crate::config::{CEILING_BOUNCE_RESTITUTION, HEIGHT, WIDTH};
+use crate::game::bird::Bird;
+use crate::game::collision_box::CollisionBox;
+use crate::game::vector2::Vector2;
+use std::fmt;
+use std::str::FromStr;
+
+/// What happens when the bird flies into the top of the playfield.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CeilingBehaviour {
+    /// The bird dies, the classic rule.
+    Kill,
+    /// The bird is stopped at the top.
+    Clamp,
+    /// The bird is knocked back down, losing some of its speed.
+    Bounce,
+}
+
+impl FromStr for CeilingBehaviour {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "kill" => Ok(CeilingBehaviour::Kill),
+            "clamp" => Ok(CeilingBehaviour::Clamp),
+            "bounce" => Ok(CeilingBehaviour::Bounce),
+            _ => Err(format!(
+                "unknown ceiling behaviour `{s}`, expected kill, clamp or bounce"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for CeilingBehaviour {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            CeilingBehaviour::Kill => "kill",
+            CeilingBehaviour::Clamp => "clamp",
+            CeilingBehaviour::Bounce => "bounce",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// An invisible slab just above the playfield.
+pub struct Ceiling {
+    pub collision_box: CollisionBox,
+    behaviour: CeilingBehaviour,
+}
+
+impl Ceiling {
+    pub fn new(behaviour: CeilingBehaviour) -> Self {
+        Self {
+            collision_box: CollisionBox::new(
+                Vector2::new(0.0, -(HEIGHT as f32)),
+                Vector2::new(WIDTH as f32, 0.0),
+            ),
+            behaviour,
+        }
+    }
+
+    pub fn behaviour(&self) -> CeilingBehaviour {
+        self.behaviour
+    }
+
+    /// Returns whether the bird is touching a ceiling that kills.
+    pub fn kills(&self, bird: &Bird) -> bool {
+        self.behaviour == CeilingBehaviour::Kill && bird.collides_with(&self.collision_box)
+    }
+
+    /// Moves the bird back below a clamping or bouncing ceiling it flew into.
+    pub fn push_back(&self, bird: &mut Bird) -> () {
+        if !bird.collides_with(&self.collision_box) {
+            return;
+        }
+
+        match self.behaviour {
+            CeilingBehaviour::Kill => (),
+            CeilingBehaviour::Clamp => bird.deflect_down(self.collision_box.max.y, 0.0),
+            CeilingBehaviour::Bounce => {
+                bird.deflect_down(self.collision_box.max.y, CEILING_BOUNCE_RESTITUTION)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Ceiling, CeilingBehaviour};
+    use crate::game::bird::Bird;
+    use crate::game::{ColliderShape, PhysicsPreset};
+
+    const DT: f32 = 1.0 / 120.0;
+
+    /// Flaps continuously into the ceiling, then lets the bird fall for `fall_ticks`.
+    fn fly_into(behaviour: CeilingBehaviour, fall_ticks: u32) -> (Ceiling, Bird) {
+        let ceiling = Ceiling::new(behaviour);
+        let mut bird = Bird::new(PhysicsPreset::Classic.physics(), ColliderShape::Circle);
+
+        for tick in 0..240 + fall_ticks {
+            if tick < 240 {
+                bird.fly();
+            }
+            bird.update(DT);
+            ceiling.push_back(&mut bird);
+        }
+
+        (ceiling, bird)
+    }
+
+    #[test]
+    fn kill_ceiling_kills() {
+        let (ceiling, bird) = fly_into(CeilingBehaviour::Kill, 0);
+
+        assert!(ceiling.kills(&bird));
+    }
+
+    #[test]
+    fn clamp_and_bounce_keep_the_bird_in_the_playfield() {
+        let (clamp, clamped) = fly_into(CeilingBehaviour::Clamp, 0);
+        let (bounce, bounced) = fly_into(CeilingBehaviour::Bounce, 0);
+
+        assert_eq!(clamped.position.y, 0.0);
+        assert_eq!(bounced.position.y, 0.0);
+        assert!(!clamp.kills(&clamped) && !bounce.kills(&bounced));
+
+        // The bounce sends the bird down faster than a dead stop.
+        let (_, clamped) = fly_into(CeilingBehaviour::Clamp, 30);
+        let (_, bounced) = fly_into(CeilingBehaviour::Bounce, 30);
+        assert!(bounced.position.y > clamped.position.y);
+    }
+}
@@ -0,0 +1,67 @@
+use crate::config::{COIN_COLOR, COIN_EDGE_COLOR, COIN_SIZE};
+use crate::game::collision_box::{CollisionBox, Hitbox};
+use crate::game::draw_utils::{draw_circle_outline, draw_pixel};
+use crate::game::vector2::Vector2;
+
+/// A coin placed in a level, worth points when the bird flies through it.
+/// A coin's position represents the top left corner of it.
+pub struct Coin {
+    pub position: Vector2,
+    /// Position before the last update, used to interpolate rendering between updates.
+    previous_position: Vector2,
+    velocity: Vector2,
+    pub collected: bool,
+    hitbox: Hitbox,
+}
+
+impl Coin {
+    pub fn new(position: Vector2, speed: f32) -> Self {
+        Self {
+            position,
+            previous_position: position,
+            velocity: Vector2::left() * speed,
+            collected: false,
+            hitbox: Hitbox::new(
+                Vector2::new(0.0, 0.0),
+                Vector2::new(COIN_SIZE as f32, COIN_SIZE as f32),
+            ),
+        }
+    }
+
+    pub fn update(&mut self, dt: f32) -> () {
+        self.previous_position = self.position;
+        self.position = self.position + self.velocity * dt;
+    }
+
+    pub fn collision_box(&self) -> CollisionBox {
+        self.hitbox.at(self.position)
+    }
+
+    /// Draws the coin `alpha` of the way from its previous position to its current one,
+    /// unless it has been collected.
+    pub fn draw(&self, frame: &mut [u8], alpha: f32) -> () {
+        if self.collected {
+            return;
+        }
+
+        let radius = COIN_SIZE as f32 / 2.0;
+        let center =
+            self.previous_position.lerp(self.position, alpha) + Vector2::new(radius, radius);
+        for y in 0..COIN_SIZE as i32 {
+            for x in 0..COIN_SIZE as i32 {
+                let offset =
+                    Vector2::new(x as f32 + 0.5, y as f32 + 0.5) - Vector2::new(radius, radius);
+                if offset.length_squared() <= radius * radius {
+                    let point = center + offset;
+                    draw_pixel(
+                        frame,
+                        point.x.floor() as i32,
+                        point.y.floor() as i32,
+                        COIN_COLOR,
+                    );
+                }
+            }
+        }
+        draw_circle_outline(frame, center, radius - 0.5, COIN_EDGE_COLOR);
+    }
+}
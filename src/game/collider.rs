@@ -0,0 +1,310 @@
+use crate::game::collision_box::CollisionBox;
+use crate::game::draw_utils::{draw_circle_outline, draw_line, draw_pixel, draw_rect_outline};
+use crate::game::sprite::Sprite;
+use crate::game::vector2::Vector2;
+use std::fmt;
+use std::str::FromStr;
+
+/// Which shape the bird collides with, picked in the settings.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColliderShape {
+    /// The bird's whole box, corners included.
+    Aabb,
+    /// The disc of the round sprite.
+    Circle,
+    /// A horizontal pill, slightly forgiving at the top and bottom.
+    Capsule,
+    /// The sprite's opaque pixels.
+    Mask,
+}
+
+impl FromStr for ColliderShape {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "aabb" => Ok(ColliderShape::Aabb),
+            "circle" => Ok(ColliderShape::Circle),
+            "capsule" => Ok(ColliderShape::Capsule),
+            "mask" => Ok(ColliderShape::Mask),
+            _ => Err(format!(
+                "unknown collider shape `{s}`, expected aabb, circle, capsule or mask"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ColliderShape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ColliderShape::Aabb => "aabb",
+            ColliderShape::Circle => "circle",
+            ColliderShape::Capsule => "capsule",
+            ColliderShape::Mask => "mask",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Which pixels of a sprite are solid.
+pub struct Mask {
+    width: u32,
+    height: u32,
+    bits: Vec<bool>,
+}
+
+impl Mask {
+    /// Every pixel with non-zero alpha is solid.
+    pub fn from_alpha(sprite: &Sprite) -> Self {
+        let bits = (0..sprite.height)
+            .flat_map(|y| (0..sprite.width).map(move |x| (x, y)))
+            .map(|(x, y)| sprite.is_opaque(x, y))
+            .collect();
+
+        Self {
+            width: sprite.width,
+            height: sprite.height,
+            bits,
+        }
+    }
+
+    /// Returns whether (x, y) is solid, anything outside the mask is not.
+    fn is_set(&self, x: i32, y: i32) -> bool {
+        x >= 0
+            && y >= 0
+            && (x as u32) < self.width
+            && (y as u32) < self.height
+            && self.bits[(y as u32 * self.width + x as u32) as usize]
+    }
+}
+
+/// A collision shape in playfield coordinates.
+pub enum Collider<'a> {
+    Aabb(CollisionBox),
+    Circle {
+        center: Vector2,
+        radius: f32,
+    },
+    /// Every point within `radius` of the segment from `start` to `end`.
+    Capsule {
+        start: Vector2,
+        end: Vector2,
+        radius: f32,
+    },
+    /// A mask whose top left corner is at `origin`, each set bit is a 1x1 square.
+    Mask {
+        origin: Vector2,
+        mask: &'a Mask,
+    },
+}
+
+impl Collider<'_> {
+    /// Returns whether the shape overlaps the box.
+    /// Like `CollisionBox::collides_with`, merely touching doesn't count.
+    pub fn collides_with_box(&self, collision_box: &CollisionBox) -> bool {
+        match self {
+            Collider::Aabb(own_box) => own_box.collides_with(collision_box),
+            Collider::Circle { center, radius } => {
+                distance_squared_to_box(*center, collision_box) < radius * radius
+            }
+            Collider::Capsule { start, end, radius } => {
+                segment_distance_squared_to_box(*start, *end, collision_box) < radius * radius
+            }
+            Collider::Mask { origin, mask } => {
+                let bounds = CollisionBox::new(
+                    *origin,
+                    *origin + Vector2::new(mask.width as f32, mask.height as f32),
+                );
+                if !bounds.collides_with(collision_box) {
+                    return false;
+                }
+
+                (0..mask.height as i32).any(|y| {
+                    (0..mask.width as i32).any(|x| {
+                        let min = *origin + Vector2::new(x as f32, y as f32);
+                        mask.is_set(x, y)
+                            && CollisionBox::new(min, min + Vector2::new(1.0, 1.0))
+                                .collides_with(collision_box)
+                    })
+                })
+            }
+        }
+    }
+
+    /// Outlines the shape, for the debug overlay.
+    pub fn draw_outline(&self, frame: &mut [u8], color: [u8; 4]) -> () {
+        match self {
+            Collider::Aabb(own_box) => draw_rect_outline(frame, own_box.min, own_box.max, color),
+            Collider::Circle { center, radius } => {
+                draw_circle_outline(frame, *center, *radius, color)
+            }
+            Collider::Capsule { start, end, radius } => {
+                draw_circle_outline(frame, *start, *radius, color);
+                draw_circle_outline(frame, *end, *radius, color);
+
+                // The straight sides are the segment pushed out perpendicularly by the radius.
+                let direction = *end - *start;
+                let length = direction.length_squared().sqrt();
+                if length > 0.0 {
+                    let normal = Vector2::new(-direction.y, direction.x) * (radius / length);
+                    draw_line(frame, *start + normal, *end + normal, color);
+                    draw_line(frame, *start - normal, *end - normal, color);
+                }
+            }
+            Collider::Mask { origin, mask } => {
+                // Only the set pixels next to an unset one, so the outline stays readable.
+                for y in 0..mask.height as i32 {
+                    for x in 0..mask.width as i32 {
+                        let is_edge = mask.is_set(x, y)
+                            && [(-1, 0), (1, 0), (0, -1), (0, 1)]
+                                .iter()
+                                .any(|(dx, dy)| !mask.is_set(x + dx, y + dy));
+                        if is_edge {
+                            draw_pixel(
+                                frame,
+                                (origin.x + x as f32).floor() as i32,
+                                (origin.y + y as f32).floor() as i32,
+                                color,
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn distance_squared_to_box(point: Vector2, collision_box: &CollisionBox) -> f32 {
+    let closest = Vector2::new(
+        point.x.clamp(collision_box.min.x, collision_box.max.x),
+        point.y.clamp(collision_box.min.y, collision_box.max.y),
+    );
+    (point - closest).length_squared()
+}
+
+fn distance_squared_to_segment(point: Vector2, start: Vector2, end: Vector2) -> f32 {
+    let direction = end - start;
+    let length_squared = direction.length_squared();
+    if length_squared == 0.0 {
+        return (point - start).length_squared();
+    }
+
+    let t = ((point - start).dot(direction) / length_squared).clamp(0.0, 1.0);
+    (point - start.lerp(end, t)).length_squared()
+}
+
+fn segment_distance_squared_to_box(
+    start: Vector2,
+    end: Vector2,
+    collision_box: &CollisionBox,
+) -> f32 {
+    if segment_intersects_box(start, end, collision_box) {
+        return 0.0;
+    }
+
+    // Apart, the closest points are an end of the segment or a corner of the box.
+    let corners = [
+        collision_box.min,
+        Vector2::new(collision_box.max.x, collision_box.min.y),
+        collision_box.max,
+        Vector2::new(collision_box.min.x, collision_box.max.y),
+    ];
+    corners
+        .iter()
+        .map(|&corner| distance_squared_to_segment(corner, start, end))
+        .chain([
+            distance_squared_to_box(start, collision_box),
+            distance_squared_to_box(end, collision_box),
+        ])
+        .fold(f32::INFINITY, f32::min)
+}
+
+/// Clips the segment against each pair of box edges in turn.
+fn segment_intersects_box(start: Vector2, end: Vector2, collision_box: &CollisionBox) -> bool {
+    let direction = end - start;
+    let (mut enter, mut exit) = (0.0_f32, 1.0_f32);
+
+    for (origin, delta, min, max) in [
+        (
+            start.x,
+            direction.x,
+            collision_box.min.x,
+            collision_box.max.x,
+        ),
+        (
+            start.y,
+            direction.y,
+            collision_box.min.y,
+            collision_box.max.y,
+        ),
+    ] {
+        if delta == 0.0 {
+            if origin < min || origin > max {
+                return false;
+            }
+            continue;
+        }
+
+        let (t0, t1) = ((min - origin) / delta, (max - origin) / delta);
+        enter = enter.max(t0.min(t1));
+        exit = exit.min(t0.max(t1));
+    }
+
+    enter <= exit
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Collider, Mask};
+    use crate::game::collision_box::CollisionBox;
+    use crate::game::sprite::Sprite;
+    use crate::game::vector2::Vector2;
+
+    /// A 10x10 box whose top left corner is at (x, y).
+    fn square(x: f32, y: f32) -> CollisionBox {
+        CollisionBox::new(Vector2::new(x, y), Vector2::new(x + 10.0, y + 10.0))
+    }
+
+    #[test]
+    fn circle_ignores_the_corners_of_its_box() {
+        let circle = Collider::Circle {
+            center: Vector2::new(15.0, 15.0),
+            radius: 15.0,
+        };
+        // Overlaps the circle's bounding box only at its top left corner.
+        let corner = square(-8.0, -8.0);
+        let side = square(-8.0, 10.0);
+
+        assert!(!circle.collides_with_box(&corner));
+        assert!(circle.collides_with_box(&side));
+    }
+
+    #[test]
+    fn capsule_covers_its_segment() {
+        let capsule = Collider::Capsule {
+            start: Vector2::new(0.0, 0.0),
+            end: Vector2::new(100.0, 0.0),
+            radius: 5.0,
+        };
+
+        // Crossing the middle of the segment, far from either end.
+        assert!(capsule.collides_with_box(&square(45.0, -5.0)));
+        assert!(capsule.collides_with_box(&square(45.0, -14.0)));
+        assert!(!capsule.collides_with_box(&square(45.0, -16.0)));
+        assert!(!capsule.collides_with_box(&square(106.0, -5.0)));
+    }
+
+    #[test]
+    fn bird_mask_matches_the_round_sprite() {
+        let sprite = Sprite::bird();
+        let mask = Mask::from_alpha(&sprite);
+        let collider = Collider::Mask {
+            origin: Vector2::new(0.0, 0.0),
+            mask: &mask,
+        };
+
+        assert!(!collider.collides_with_box(&square(-8.0, -8.0)));
+        assert!(collider.collides_with_box(&square(10.0, 10.0)));
+        assert!(collider.collides_with_box(&square(-8.0, 10.0)));
+    }
+}
@@ -0,0 +1,245 @@
+use crate::config::{
+    CONSOLE_BACKGROUND_COLOR, CONSOLE_HISTORY_LINES, CONSOLE_MAX_INPUT, DEBUG_FONT_PIXEL_SIZE,
+    WIDTH,
+};
+use crate::game::draw_utils::{draw_string_sized, is_drawable};
+use crate::game::font::font;
+use std::str::FromStr;
+
+/// A command typed into the developer console.
+#[derive(Debug, PartialEq)]
+pub enum ConsoleCommand {
+    /// Scales the physics preset's gravity.
+    SetGravity(f32),
+    /// Scales the physics preset's flap speed.
+    SetFlapSpeed(f32),
+    /// Restarts the run with the given seed.
+    Seed(u64),
+    /// Turns death on or off.
+    God(bool),
+    SpawnPipe,
+    Score(u32),
+    /// Fast-forwards the run to the given tick.
+    Tick(u64),
+    /// How fast the clock runs, 1 is real time.
+    Timescale(f32),
+}
+
+impl FromStr for ConsoleCommand {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words: Vec<&str> = s.split_whitespace().collect();
+
+        match words[..] {
+            ["set", "gravity", scale] => Ok(ConsoleCommand::SetGravity(number(scale)?)),
+            ["set", "flap", scale] => Ok(ConsoleCommand::SetFlapSpeed(number(scale)?)),
+            ["seed", seed] => Ok(ConsoleCommand::Seed(number(seed)?)),
+            ["god", "on"] => Ok(ConsoleCommand::God(true)),
+            ["god", "off"] => Ok(ConsoleCommand::God(false)),
+            ["spawn_pipe"] => Ok(ConsoleCommand::SpawnPipe),
+            ["score", score] => Ok(ConsoleCommand::Score(number(score)?)),
+            ["tick", tick] => Ok(ConsoleCommand::Tick(number(tick)?)),
+            ["timescale", scale] => match number(scale)? {
+                scale if scale > 0.0 && scale <= 10.0 => Ok(ConsoleCommand::Timescale(scale)),
+                _ => Err("timescale must be above 0 and at most 10".to_string()),
+            },
+            _ => Err(format!("unknown command {s}")),
+        }
+    }
+}
+
+fn number<T: FromStr>(word: &str) -> Result<T, String> {
+    word.parse()
+        .map_err(|_| format!("{word} is not a valid number"))
+}
+
+/// An input line with a command history, toggled with the backtick key.
+pub struct Console {
+    open: bool,
+    input: String,
+    /// Submitted lines, oldest first.
+    history: Vec<String>,
+    /// Which history entry the input was recalled from while browsing with the arrows.
+    browsing: Option<usize>,
+    /// Result of the last command.
+    output: String,
+}
+
+impl Console {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            input: String::new(),
+            history: Vec::new(),
+            browsing: None,
+            output: String::new(),
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn toggle(&mut self) -> () {
+        self.open = !self.open;
+    }
+
+    /// Appends typed text, dropping anything the font can't draw.
+    pub fn type_text(&mut self, text: &str) -> () {
+        for c in text.chars().map(|c| c.to_ascii_lowercase()) {
+            if is_drawable(c) && self.input.len() < CONSOLE_MAX_INPUT {
+                self.input.push(c);
+            }
+        }
+    }
+
+    pub fn backspace(&mut self) -> () {
+        self.input.pop();
+    }
+
+    /// Recalls the entry before the one shown, starting from the most recent.
+    pub fn history_previous(&mut self) -> () {
+        let index = match self.browsing {
+            Some(0) => 0,
+            Some(index) => index - 1,
+            None if self.history.is_empty() => return,
+            None => self.history.len() - 1,
+        };
+        self.browsing = Some(index);
+        self.input = self.history[index].clone();
+    }
+
+    /// Recalls the entry after the one shown, or clears the input past the newest.
+    pub fn history_next(&mut self) -> () {
+        match self.browsing {
+            Some(index) if index + 1 < self.history.len() => {
+                self.browsing = Some(index + 1);
+                self.input = self.history[index + 1].clone();
+            }
+            Some(_) => {
+                self.browsing = None;
+                self.input.clear();
+            }
+            None => (),
+        }
+    }
+
+    /// Takes the input line, remembering it in the history. Returns None if it is blank.
+    pub fn submit(&mut self) -> Option<String> {
+        let line = std::mem::take(&mut self.input).trim().to_string();
+        self.browsing = None;
+        if line.is_empty() {
+            return None;
+        }
+
+        self.history.push(line.clone());
+        Some(line)
+    }
+
+    pub fn set_output(&mut self, output: String) -> () {
+        self.output = output;
+    }
+
+    /// Draws the console as a panel across the top of the screen.
+    pub fn draw(&self, frame: &mut [u8]) -> () {
+        let line_height = DEBUG_FONT_PIXEL_SIZE * (font().line_height() + 1);
+        let margin = DEBUG_FONT_PIXEL_SIZE * 4;
+
+        let recent = self.history.len().saturating_sub(CONSOLE_HISTORY_LINES);
+        let mut lines: Vec<String> = self.history[recent..]
+            .iter()
+            .map(|line| format!("> {line}"))
+            .collect();
+        lines.push(self.output.clone());
+        lines.push(format!("> {}_", self.input));
+
+        // A tall font can't make the panel taller than the frame.
+        let height = ((CONSOLE_HISTORY_LINES as u32 + 2) * line_height + 2 * margin)
+            .min((frame.len() / (WIDTH as usize * 4)) as u32);
+        for pixel in frame[..(height * WIDTH * 4) as usize].chunks_exact_mut(4) {
+            pixel.copy_from_slice(&CONSOLE_BACKGROUND_COLOR);
+        }
+
+        // Anchor the input line to the bottom of the panel.
+        let first_y = height.saturating_sub(margin + lines.len() as u32 * line_height);
+        for (i, line) in lines.iter().enumerate() {
+            // Output can come from anywhere, keep only what the font can draw.
+            let line: String = line
+                .chars()
+                .map(|c| c.to_ascii_lowercase())
+                .filter(|&c| is_drawable(c))
+                .collect();
+            draw_string_sized(
+                frame,
+                &line,
+                margin,
+                first_y + i as u32 * line_height,
+                DEBUG_FONT_PIXEL_SIZE,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Console, ConsoleCommand};
+    use crate::config::{CONSOLE_BACKGROUND_COLOR, WIDTH};
+
+    #[test]
+    fn parses_commands() {
+        assert_eq!(
+            "set gravity 1.5".parse(),
+            Ok(ConsoleCommand::SetGravity(1.5))
+        );
+        assert_eq!("seed 1234".parse(), Ok(ConsoleCommand::Seed(1234)));
+        assert_eq!(" god  on ".parse(), Ok(ConsoleCommand::God(true)));
+        assert_eq!("spawn_pipe".parse(), Ok(ConsoleCommand::SpawnPipe));
+        assert_eq!("score 50".parse(), Ok(ConsoleCommand::Score(50)));
+        assert_eq!("tick 600".parse(), Ok(ConsoleCommand::Tick(600)));
+        assert_eq!("timescale 0.5".parse(), Ok(ConsoleCommand::Timescale(0.5)));
+
+        assert!("timescale 0".parse::<ConsoleCommand>().is_err());
+        assert!("score lots".parse::<ConsoleCommand>().is_err());
+        assert!("fly".parse::<ConsoleCommand>().is_err());
+    }
+
+    #[test]
+    fn arrows_browse_the_history() {
+        let mut console = Console::new();
+        for line in ["seed 1", "god on"] {
+            console.type_text(line);
+            console.submit();
+        }
+
+        console.history_previous();
+        assert_eq!(console.input, "god on");
+        console.history_previous();
+        console.history_previous();
+        assert_eq!(console.input, "seed 1");
+        console.history_next();
+        assert_eq!(console.input, "god on");
+        console.history_next();
+        assert_eq!(console.input, "");
+
+        // Typed text is lowercased and undrawable characters are dropped.
+        console.type_text("Score€ 5");
+        assert_eq!(console.input, "score 5");
+    }
+
+    #[test]
+    fn panel_fits_frames_shorter_than_it() {
+        let mut console = Console::new();
+        console.toggle();
+        console.type_text("seed 1");
+
+        // As if a tall font made the panel taller than the frame.
+        let mut frame = vec![0; (WIDTH * 10 * 4) as usize];
+        console.draw(&mut frame);
+        assert!(
+            frame
+                .chunks_exact(4)
+                .all(|pixel| pixel == CONSOLE_BACKGROUND_COLOR)
+        );
+    }
+}
@@ -0,0 +1,133 @@
+use crate::config::{DIFFICULTY_CURVE, PIPE_GAP_SIZE, PIPE_SPACING, PIPE_SPEED};
+use std::fmt;
+use std::str::FromStr;
+
+/// How the pipes are set up for a run.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PipeTuning {
+    /// Leftwards speed in pixels per second.
+    pub speed: f32,
+    /// Height of the gap between the halves in pixels.
+    pub gap_size: u32,
+    /// Horizontal space between one pipe and the next in pixels.
+    pub spacing: u32,
+}
+
+/// A step of the progressive curve, reached once the score gets to `score`.
+/// The scales apply to the difficulty's own tuning.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CurveStage {
+    pub score: u32,
+    pub speed_scale: f32,
+    pub gap_scale: f32,
+}
+
+/// Returns the index of the curve stage a run with the given score is at.
+pub fn curve_stage(score: u32) -> usize {
+    DIFFICULTY_CURVE
+        .iter()
+        .rposition(|stage| stage.score <= score)
+        .unwrap_or(0)
+}
+
+/// How hard the pipes are, picked in the settings.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Difficulty {
+    /// Slower pipes with wider gaps.
+    Easy,
+    Normal,
+    /// Faster pipes with narrower gaps.
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+    pub fn pipes(self) -> PipeTuning {
+        match self {
+            Difficulty::Easy => PipeTuning {
+                speed: 70.0,
+                gap_size: 150,
+                spacing: 230,
+            },
+            Difficulty::Normal => PipeTuning {
+                speed: PIPE_SPEED,
+                gap_size: PIPE_GAP_SIZE,
+                spacing: PIPE_SPACING,
+            },
+            Difficulty::Hard => PipeTuning {
+                speed: 100.0,
+                gap_size: 105,
+                spacing: 210,
+            },
+        }
+    }
+
+    /// The pipes at the given stage of the progressive curve, faster and narrower the
+    /// further along it is.
+    pub fn pipes_at(self, stage: usize) -> PipeTuning {
+        let base = self.pipes();
+        let stage = DIFFICULTY_CURVE[stage];
+        PipeTuning {
+            speed: base.speed * stage.speed_scale,
+            gap_size: (base.gap_size as f32 * stage.gap_scale).round() as u32,
+            spacing: base.spacing,
+        }
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "easy" => Ok(Difficulty::Easy),
+            "normal" => Ok(Difficulty::Normal),
+            "hard" => Ok(Difficulty::Hard),
+            _ => Err(format!(
+                "unknown difficulty `{s}`, expected easy, normal or hard"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "hard",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Difficulty, curve_stage};
+    use crate::config::DIFFICULTY_CURVE;
+
+    #[test]
+    fn curve_gets_faster_and_narrower_with_the_score() {
+        assert_eq!(DIFFICULTY_CURVE[0].score, 0);
+        assert_eq!(curve_stage(0), 0);
+        assert_eq!(curve_stage(u32::MAX), DIFFICULTY_CURVE.len() - 1);
+
+        for difficulty in Difficulty::ALL {
+            assert_eq!(difficulty.pipes_at(0), difficulty.pipes());
+
+            for stages in DIFFICULTY_CURVE.windows(2) {
+                assert!(stages[0].score < stages[1].score);
+                assert_eq!(
+                    curve_stage(stages[1].score - 1),
+                    curve_stage(stages[0].score)
+                );
+
+                let (before, after) = (curve_stage(stages[0].score), curve_stage(stages[1].score));
+                assert_eq!(after, before + 1);
+                assert!(difficulty.pipes_at(after).speed > difficulty.pipes_at(before).speed);
+                assert!(difficulty.pipes_at(after).gap_size < difficulty.pipes_at(before).gap_size);
+            }
+        }
+    }
+}
@@ -1,133 +1,138 @@
-use crate::config::{
-    DIGITS, FONT_HEIGHT_PIXEL_MAP_PIXELS, FONT_PIXEL_SIZE_SCREEN_PIXELS, FONT_START_OFFSET,
-    FONT_WIDTH_SIZE_PIXEL_MAP_PIXELS, LETTERS, SPECIALS, UI_A_COLOR, UI_B_COLOR, UI_G_COLOR,
-    UI_R_COLOR, WIDTH,
-};
-use std::io::Error;
-use std::io::ErrorKind::InvalidInput;
-
-/// Draws the given number onto the screen.
-pub fn draw_number(frame: &mut [u8], score: u32) -> () {
-    let mut score = score;
-    let offset = FONT_PIXEL_SIZE_SCREEN_PIXELS * FONT_WIDTH_SIZE_PIXEL_MAP_PIXELS;
-
-    // Still want to render zero
-    if score == 0 {
-        draw_digit(frame, 0, FONT_START_OFFSET, FONT_START_OFFSET).unwrap();
+use crate::config::WIDTH;
+use crate::game::font::font;
+use crate::game::text::{TextStyle, draw_text};
+use crate::game::vector2::Vector2;
+
+/// Draws the given string at the location (x, y), the top left corner of the string in
+/// pixel coordinates of the given frame, with each font pixel `pixel_size` screen pixels wide.
+pub fn draw_string_sized(frame: &mut [u8], string: &str, x: u32, y: u32, pixel_size: u32) -> () {
+    draw_text(
+        frame,
+        string,
+        x as i32,
+        y as i32,
+        &TextStyle::new().with_scale(pixel_size),
+    );
+}
+
+/// Returns whether the font has a glyph for the character.
+/// Letters of either case share a glyph unless the font has both.
+pub fn is_drawable(c: char) -> bool {
+    font().glyph(c).is_some()
+}
+
+/// Sets the pixel at (x, y) to the given colour, ignoring anything outside the frame.
+pub fn draw_pixel(frame: &mut [u8], x: i32, y: i32, color: [u8; 4]) -> () {
+    if x < 0 || y < 0 || x >= WIDTH as i32 {
         return;
     }
 
-    // Compute divisor to get most significant digit first
-    let mut div = 1;
-    while score / div >= 10 {
-        div *= 10;
+    let idx = (y as usize * WIDTH as usize + x as usize) * 4;
+    if idx + 4 > frame.len() {
+        return;
     }
 
-    let mut x_offset = FONT_START_OFFSET;
-
-    while div > 0 {
-        let digit = score / div;
-        draw_digit(frame, digit, x_offset, FONT_START_OFFSET).unwrap();
-        score %= div;
-        div /= 10;
-        x_offset += offset;
-    }
+    frame[idx..idx + 4].copy_from_slice(&color);
 }
 
-/// Draws the given digit at the location (x, y)
-/// Where x and y are in pixel coordinates of the given frame.
-/// The x and y are the top left corner of the digit.
-fn draw_digit(frame: &mut [u8], digit: u32, x: u32, y: u32) -> Result<(), Error> {
-    if digit > 9 {
-        return Err(Error::new(InvalidInput, "digit must be between 0–9"));
-    }
-
-    let pixel_map = &DIGITS[digit as usize];
+/// Draws a one pixel wide line from `start` to `end`.
+pub fn draw_line(frame: &mut [u8], start: Vector2, end: Vector2, color: [u8; 4]) -> () {
+    let delta = end - start;
+    let steps = delta.x.abs().max(delta.y.abs()).ceil().max(1.0);
 
-    draw_pixel_map(frame, pixel_map, x, y);
+    for step in 0..=steps as u32 {
+        let point = start.lerp(end, step as f32 / steps);
+        draw_pixel(frame, point.x.floor() as i32, point.y.floor() as i32, color);
+    }
+}
 
-    Ok(())
+/// Draws the outline of the rectangle spanning `min` to `max`.
+pub fn draw_rect_outline(frame: &mut [u8], min: Vector2, max: Vector2, color: [u8; 4]) -> () {
+    // Outline the pixels just inside the rectangle.
+    let max = max + Vector2::new(-1.0, -1.0);
+    let top_right = Vector2::new(max.x, min.y);
+    let bottom_left = Vector2::new(min.x, max.y);
+
+    draw_line(frame, min, top_right, color);
+    draw_line(frame, top_right, max, color);
+    draw_line(frame, max, bottom_left, color);
+    draw_line(frame, bottom_left, min, color);
 }
 
-/// Draws the given string at the location (x, y)
-/// Where x and y are in pixel coordinates of the given frame.
-/// The x and y are the top left corner of the string.
-pub fn draw_string(frame: &mut [u8], string: &str, x: u32, y: u32) -> () {
-    let offset = FONT_PIXEL_SIZE_SCREEN_PIXELS * FONT_WIDTH_SIZE_PIXEL_MAP_PIXELS;
+/// Draws the outline of a circle.
+pub fn draw_circle_outline(frame: &mut [u8], center: Vector2, radius: f32, color: [u8; 4]) -> () {
+    // Enough points that neighbouring ones are at most a pixel apart.
+    let points = (std::f32::consts::TAU * radius).ceil().max(8.0) as u32;
 
-    let mut x = x;
-    for c in string.chars() {
-        draw_char(frame, c, x, y);
-        x += offset;
+    for i in 0..points {
+        let angle = std::f32::consts::TAU * i as f32 / points as f32;
+        let point = center + Vector2::new(angle.cos(), angle.sin()) * radius;
+        draw_pixel(frame, point.x.floor() as i32, point.y.floor() as i32, color);
     }
 }
 
-/// Draws the given character at the location (x, y)
-/// Where x and y are in pixel coordinates of the given frame.
-/// The x and y are the top left corner of the character.
-fn draw_char(frame: &mut [u8], c: char, x: u32, y: u32) -> () {
-    if c.is_ascii_digit() {
-        draw_digit(frame, c.to_digit(10).unwrap(), x, y).unwrap();
-        return;
+#[cfg(test)]
+mod tests {
+    use super::{draw_string_sized, is_drawable};
+    use crate::config::{
+        FONT_PIXEL_SIZE_SCREEN_PIXELS, FONT_START_OFFSET, HEIGHT, MISSING_GLYPH, WIDTH,
+    };
+
+    fn render(string: &str) -> Vec<u8> {
+        let mut frame = vec![0; (WIDTH * HEIGHT * 4) as usize];
+        draw_string_sized(
+            &mut frame,
+            string,
+            FONT_START_OFFSET,
+            FONT_START_OFFSET,
+            FONT_PIXEL_SIZE_SCREEN_PIXELS,
+        );
+        frame
     }
 
-    let pixel_map;
-
-    // Handle special character edge cases first
-    match c {
-        '(' => pixel_map = &SPECIALS[0],
-        ')' => pixel_map = &SPECIALS[1],
-        '!' => pixel_map = &SPECIALS[2],
-        '?' => pixel_map = &SPECIALS[3],
-        '/' => pixel_map = &SPECIALS[4],
-        ' ' => return,
-        _ => {
-            let idx = (c as u8 - b'a') as usize; // b'a' == 97u8
-            pixel_map = &LETTERS[idx];
+    #[test]
+    fn every_printable_ascii_char_has_a_glyph() {
+        let missing = render("\u{2603}");
+
+        for c in (' '..='~').filter(|&c| c != ' ') {
+            let frame = render(&c.to_string());
+
+            assert!(is_drawable(c), "{c:?} is not drawable");
+            assert!(frame.iter().any(|&b| b != 0), "{c:?} drew nothing");
+            assert!(frame != missing, "{c:?} drew the missing glyph");
         }
+
+        assert!(render("A") == render("a"));
+        assert!(MISSING_GLYPH.iter().any(|&bit| bit));
     }
 
-    draw_pixel_map(frame, pixel_map, x, y);
-}
+    #[test]
+    fn unsupported_chars_draw_the_missing_glyph() {
+        let missing = render("\u{2603}");
 
-/// Draws the given pixel map at the location (x, y)
-/// Where x and y are in pixel coordinates of the given frame.
-/// The x and y are the top left corner of the pixel map.
-fn draw_pixel_map(frame: &mut [u8], pixel_map: &[bool; 63], x: u32, y: u32) -> () {
-    for i in 0..FONT_WIDTH_SIZE_PIXEL_MAP_PIXELS {
-        for j in 0..FONT_HEIGHT_PIXEL_MAP_PIXELS {
-            let idx = (j * FONT_WIDTH_SIZE_PIXEL_MAP_PIXELS + i) as usize;
-
-            if pixel_map[idx] {
-                draw_font_pixel(frame, x, y, i, j);
-            }
+        for c in ['ø', 'ß', '€', '\t', '\u{7f}'] {
+            assert!(!is_drawable(c));
+            assert!(render(&c.to_string()) == missing, "{c:?}");
         }
     }
-}
 
-/// Draws a font pixel at the given location (x, y)
-/// Where x and y are in pixel coordinates of the given frame and represent the location of the digit.
-/// And where x_pixel and y_pixel are in coordinates of the digit pixel map.
-fn draw_font_pixel(frame: &mut [u8], x: u32, y: u32, x_pixel: u32, y_pixel: u32) -> () {
-    // The location of the digit pixel is the top left corner of it.
-    // It is in pixel coordinates
-    let x_digit_pixel_location = x + x_pixel * FONT_PIXEL_SIZE_SCREEN_PIXELS;
-    let y_digit_pixel_location = y + y_pixel * FONT_PIXEL_SIZE_SCREEN_PIXELS;
-
-    let stride = WIDTH * 4;
-
-    for x in x_digit_pixel_location..x_digit_pixel_location + FONT_PIXEL_SIZE_SCREEN_PIXELS {
-        for y in y_digit_pixel_location..y_digit_pixel_location + FONT_PIXEL_SIZE_SCREEN_PIXELS {
-            let idx = (y * stride + x * 4) as usize;
-
-            if idx > frame.len() {
-                break;
-            }
-
-            frame[idx + 0] = UI_R_COLOR;
-            frame[idx + 1] = UI_G_COLOR;
-            frame[idx + 2] = UI_B_COLOR;
-            frame[idx + 3] = UI_A_COLOR;
-        }
+    #[test]
+    fn text_off_the_edges_is_clipped() {
+        let mut frame = vec![0; (WIDTH * HEIGHT * 4) as usize];
+        draw_string_sized(
+            &mut frame,
+            "clipped text!",
+            WIDTH - 20,
+            HEIGHT - 20,
+            FONT_PIXEL_SIZE_SCREEN_PIXELS,
+        );
+
+        // Nothing wrapped around to the left side.
+        let drawn_columns = frame
+            .chunks_exact(4)
+            .enumerate()
+            .filter(|(_, pixel)| *pixel != [0; 4])
+            .map(|(i, _)| i as u32 % WIDTH);
+        assert!(drawn_columns.into_iter().all(|x| x >= WIDTH - 20));
     }
 }
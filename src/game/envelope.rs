@@ -0,0 +1,585 @@
+use crate::config::{
+    BIRD_HEIGHT, BIRD_WIDTH, DEFAULT_TICK_RATE, FAIR_FLAP_INTERVAL, FAIR_REACH_MARGIN, GROUND_Y,
+    PIPE_GAP_BOUND, WITNESS_MARGIN, WITNESS_SWITCH_STEP, WITNESS_TARGET_STEP,
+};
+use crate::game::bird_physics::BirdPhysics;
+use crate::game::difficulty::PipeTuning;
+use crate::game::pipe_behaviour::{Gap, PipeBehaviour};
+use crate::game::tick_rate::TickRate;
+use std::ops::Range;
+
+/// How far the bird can climb or drop between leaving one gap and reaching the next.
+/// New gaps are kept within it where they can be, so runs stay comfortable to fly.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Envelope {
+    pub climb: f32,
+    pub drop: f32,
+}
+
+impl Envelope {
+    /// The envelope of a bird with the given physics between two pipes with the given
+    /// tuning, from a standstill and flapping no faster than a player comfortably can.
+    /// It is shrunk by `FAIR_REACH_MARGIN` to leave room for the bird not leaving a gap
+    /// at rest.
+    pub fn new(physics: &BirdPhysics, tuning: PipeTuning) -> Self {
+        // From the bird clearing one pipe to it touching the next.
+        let seconds = tuning.spacing.saturating_sub(BIRD_WIDTH) as f32 / tuning.speed;
+        let dt = DEFAULT_TICK_RATE.dt();
+        let interval = FAIR_FLAP_INTERVAL.max(physics.flap_cooldown);
+
+        let (mut rise_velocity, mut rise, mut since_flap) = (0.0, 0.0, interval);
+        let (mut fall_velocity, mut fall) = (0.0, 0.0);
+        let mut time = 0.0;
+        while time < seconds {
+            if since_flap >= interval {
+                rise_velocity = physics.flap(rise_velocity);
+                since_flap = 0.0;
+            }
+            let (velocity, distance) = physics.step(rise_velocity, dt);
+            rise_velocity = velocity;
+            rise += distance;
+            since_flap += dt;
+
+            let (velocity, distance) = physics.step(fall_velocity, dt);
+            fall_velocity = velocity;
+            fall += distance;
+
+            time += dt;
+        }
+
+        Self {
+            climb: (-rise).max(0.0) * FAIR_REACH_MARGIN,
+            drop: fall.max(0.0) * FAIR_REACH_MARGIN,
+        }
+    }
+
+    /// The tops a gap of `size` pixels can have to be within reach of the previous gap.
+    /// Both gaps can move as far as their behaviour reaches, the bird has to make it
+    /// wherever they are when it gets there.
+    pub fn tops_after(
+        &self,
+        previous: Gap,
+        previous_reach: f32,
+        size: u32,
+        reach: f32,
+    ) -> Range<f32> {
+        let slack = previous_reach + reach;
+        previous.top - self.climb + slack..previous.bottom() + self.drop - size as f32 - slack
+    }
+}
+
+/// The stretch of a run from the bird touching the last pipe to it clearing the next one.
+/// Times are in seconds from the bird touching the last pipe.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Leg {
+    /// The last pipe's gap as it was placed, and its behaviour.
+    pub last: (Gap, PipeBehaviour),
+    /// When the bird clears the last pipe.
+    pub cleared: f32,
+    /// When the bird touches and clears the next pipe.
+    pub reached: f32,
+    pub left: f32,
+    /// The next pipe's gap size and behaviour.
+    pub size: f32,
+    pub behaviour: PipeBehaviour,
+    /// How much younger the next pipe is than the last.
+    pub younger: f32,
+}
+
+/// A flight the bird can really make through every gap placed so far, as it touches the
+/// last one. Each new gap is placed where a way of flying on from it gets through, so
+/// every run can be flown.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Witness {
+    y: f32,
+    velocity: f32,
+    /// Seconds since the flight last flapped.
+    since_flap: f32,
+    /// Seconds between the bird touching the last pipe and the flight's state.
+    late: f32,
+    /// How old the last pipe is as the bird touches it.
+    age: f32,
+    /// The height the flight holds, None for any.
+    target: Option<f32>,
+}
+
+impl Witness {
+    /// A flight starting at rest in the middle of a still gap, which the bird can get to
+    /// from anywhere as the first pipe comes in. It can hold any height at first.
+    pub fn touching(gap: Gap, age: f32) -> Self {
+        Self {
+            y: gap.top + (gap.size - BIRD_HEIGHT as f32) / 2.0,
+            velocity: 0.0,
+            since_flap: f32::INFINITY,
+            late: 0.0,
+            age,
+            target: None,
+        }
+    }
+
+    /// How old the last pipe is as the bird touches it.
+    pub fn age(&self) -> f32 {
+        self.age
+    }
+
+    /// The same flight, free to hold any height through the last gap.
+    pub fn holding_any(self) -> Self {
+        Self {
+            target: None,
+            ..self
+        }
+    }
+
+    /// Flies on through the leg holding its height through the last gap, and switching to
+    /// holding every other height at some point before the next, flapping no faster than
+    /// a player comfortably can.
+    /// Returns the tops the next gap can have for each flight that gets through, with the
+    /// flight as it touches the next pipe.
+    /// Holding a height only depends on the flight's state, and the flight that placed the
+    /// last gap got through it holding that height, so it always gets through it again.
+    pub fn flights(&self, physics: &BirdPhysics, leg: &Leg) -> Vec<(Range<f32>, Witness)> {
+        self.flights_up_to(physics, leg, usize::MAX)
+    }
+
+    /// Returns whether the flight can fly on through the leg to some gap.
+    pub fn flies_on(&self, physics: &BirdPhysics, leg: &Leg) -> bool {
+        !self.flights_up_to(physics, leg, 1).is_empty()
+    }
+
+    /// The flights through the leg, stopping once `limit` have been found.
+    fn flights_up_to(
+        &self,
+        physics: &BirdPhysics,
+        leg: &Leg,
+        limit: usize,
+    ) -> Vec<(Range<f32>, Witness)> {
+        // The slowest rate the game runs at, its flaps can be made at every rate.
+        let dt = TickRate::Hz60.dt();
+        let switch_ticks = (WITNESS_SWITCH_STEP / dt).round() as u32;
+        let targets = || (0..GROUND_Y).step_by(WITNESS_TARGET_STEP).map(|y| y as f32);
+
+        // Heights below the gap are worth holding too, the bird falls through it without
+        // flapping.
+        let firsts: Vec<f32> = match self.target {
+            Some(target) => vec![target],
+            None => targets().collect(),
+        };
+
+        let mut flights = Vec::new();
+        'firsts: for first in firsts {
+            let (mut flight, mut time) = (*self, self.late);
+            while time < leg.cleared {
+                if !flight.hold(physics, first, leg, &mut time) {
+                    continue 'firsts;
+                }
+            }
+            while time < leg.reached {
+                for second in targets() {
+                    flights.extend(flight.fly_on(physics, second, leg, time));
+                    if flights.len() >= limit {
+                        return flights;
+                    }
+                }
+                for _ in 0..switch_ticks {
+                    if !flight.hold(physics, first, leg, &mut time) {
+                        continue 'firsts;
+                    }
+                }
+            }
+        }
+        flights
+    }
+
+    /// Holds `target` from `time` until the bird clears the next pipe, returning the tops
+    /// the next gap can have and the flight as it touches the next pipe if it gets there.
+    fn fly_on(
+        mut self,
+        physics: &BirdPhysics,
+        target: f32,
+        leg: &Leg,
+        mut time: f32,
+    ) -> Option<(Range<f32>, Witness)> {
+        let dt = TickRate::Hz60.dt();
+        let height = BIRD_HEIGHT as f32;
+        let mut touching = None;
+        let (mut lowest, mut highest) = (f32::NEG_INFINITY, f32::INFINITY);
+        // A climb started by then can't be taken back, so it has to stay low enough for
+        // the gap after to be placed around it.
+        while time < leg.left || self.velocity < 0.0 {
+            let level = time < leg.left;
+            let before = self.y;
+            if !self.hold(physics, target, leg, &mut time)
+                || !level && self.y < PIPE_GAP_BOUND as f32 + WITNESS_MARGIN
+            {
+                return None;
+            }
+            if level && time > leg.reached {
+                // Behaviours move the gap the same wherever it is placed, a sliding one is
+                // only roomier before it settles.
+                let gap = |time: f32| {
+                    let placed = Gap {
+                        top: 0.0,
+                        size: leg.size,
+                    };
+                    leg.behaviour.gap(placed, self.age + time - leg.younger)
+                };
+                // Collisions are checked over the whole tick, the bird and the gap both
+                // move evenly through it, so staying clear at both ends is enough.
+                for (y, gap) in [(before, gap(time - dt)), (self.y, gap(time))] {
+                    lowest = lowest.max(y + height - gap.bottom());
+                    highest = highest.min(y - gap.top);
+                }
+                touching.get_or_insert(Witness {
+                    late: time - leg.reached,
+                    age: self.age + leg.reached - leg.younger,
+                    target: Some(target),
+                    ..self
+                });
+            }
+        }
+
+        let tops = lowest + WITNESS_MARGIN..highest - WITNESS_MARGIN;
+        touching
+            .filter(|_| tops.start <= tops.end)
+            .map(|touching| (tops, touching))
+    }
+
+    /// Flies one tick holding `target`, flapping whenever the bird has dropped below it.
+    /// Returns whether the bird is still in the playfield and clear of the last pipe.
+    fn hold(&mut self, physics: &BirdPhysics, target: f32, leg: &Leg, time: &mut f32) -> bool {
+        let dt = TickRate::Hz60.dt();
+        if self.since_flap >= FAIR_FLAP_INTERVAL.max(physics.flap_cooldown) && self.y > target {
+            self.velocity = physics.flap(self.velocity);
+            self.since_flap = 0.0;
+        }
+        let before = self.y;
+        let (velocity, distance) = physics.step(self.velocity, dt);
+        self.velocity = velocity;
+        self.y += distance;
+        self.since_flap += dt;
+        *time += dt;
+
+        let (top, bottom) = (self.y, self.y + BIRD_HEIGHT as f32);
+        if top < 0.0 || bottom > GROUND_Y as f32 {
+            return false;
+        }
+        if *time - dt >= leg.cleared {
+            return true;
+        }
+        let (placed, behaviour) = leg.last;
+        [(before, *time - dt), (self.y, *time)]
+            .into_iter()
+            .all(|(y, time)| {
+                let gap = behaviour.gap(placed, self.age + time);
+                y >= gap.top && y + BIRD_HEIGHT as f32 <= gap.bottom()
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Envelope;
+    use crate::config::{BIRD_HEIGHT, BIRD_WIDTH, DIFFICULTY_CURVE, GROUND_Y, PIPE_WIDTH};
+    use crate::game::pipe_behaviour::{Gap, PipeBehaviour};
+    use crate::game::{Difficulty, Game, PhysicsPreset, TickRate};
+    use std::ops::Range;
+    use std::thread;
+
+    /// Pixels and pixels per second covered by one cell of the search grid.
+    const CELL_HEIGHT: f32 = 2.0;
+    const CELL_SPEED: f32 = 20.0;
+    const SPEED_CELLS: usize = 101;
+    /// How far the world scrolls while the bird is level with a pipe.
+    const OVERLAP: f32 = (PIPE_WIDTH + BIRD_WIDTH) as f32;
+
+    /// A pipe of a generated run.
+    struct RunPipe {
+        placed: Gap,
+        behaviour: PipeBehaviour,
+        /// How far the world has scrolled when the bird touches it.
+        touched: f32,
+        /// When it spawned.
+        spawned: f32,
+    }
+
+    /// A generated run, with distances and times counted from the run starting. The world
+    /// scrolls at `speeds.0` until `change` seconds in, when the stage changes, and at
+    /// `speeds.1` after.
+    struct Run {
+        pipes: Vec<RunPipe>,
+        speeds: (f32, f32),
+        change: f32,
+    }
+
+    impl Run {
+        /// Plays a progressive run that reaches the stage after `stage` as the bird clears
+        /// the middle one of `pipes` pipes, with the bird out of the way, and keeps the
+        /// pipes it spawns.
+        fn generate(
+            preset: PhysicsPreset,
+            difficulty: Difficulty,
+            seed: u64,
+            stage: usize,
+            pipes: usize,
+        ) -> Self {
+            let mut game = Game::with_seed(seed)
+                .with_physics_preset(preset)
+                .with_difficulty(difficulty)
+                .with_progressive(true);
+            game.start_run();
+            game.god_mode = true;
+            let next = DIFFICULTY_CURVE[stage + 1].score;
+            let passed = (pipes as u32 / 2).min(next - DIFFICULTY_CURVE[stage].score);
+            game.score.score = next - passed;
+            game.check_for_new_stage();
+            game.pipes.clear();
+            game.spawn_pipe();
+
+            let dt = TickRate::Hz120.dt();
+            let bird = game.bird.position.x + BIRD_WIDTH as f32;
+            let mut run = Self {
+                pipes: Vec::new(),
+                speeds: (game.speed(), game.speed()),
+                change: f32::INFINITY,
+            };
+            let (mut time, mut scrolled) = (0.0, 0.0);
+            while run.pipes.len() < pipes {
+                let speed = game.speed();
+                game.update(dt);
+                scrolled += speed * dt;
+                if game.speed() != speed {
+                    run.speeds.1 = game.speed();
+                    run.change = time + dt;
+                }
+                // New pipes have been updated once.
+                for pipe in game.pipes.iter().filter(|pipe| pipe.age() <= dt) {
+                    run.pipes.push(RunPipe {
+                        placed: pipe.placed_gap(),
+                        behaviour: pipe.behaviour(),
+                        touched: scrolled + pipe.position.x - bird,
+                        spawned: time,
+                    });
+                }
+                time += dt;
+            }
+            assert!(run.change.is_finite(), "the stage never changed");
+            run
+        }
+
+        /// How far the world has scrolled after `time` seconds.
+        fn distance(&self, time: f32) -> f32 {
+            let (before, after) = self.speeds;
+            before * time.min(self.change) + after * (time - self.change).max(0.0)
+        }
+
+        /// How long the world takes to scroll `distance` pixels.
+        fn time(&self, distance: f32) -> f32 {
+            let (before, after) = self.speeds;
+            let switch = before * self.change;
+            if distance < switch {
+                distance / before
+            } else {
+                self.change + (distance - switch) / after
+            }
+        }
+
+        /// The gaps at the start and end of a tick of the pipes the bird is level with at some
+        /// point during it.
+        fn gaps(&self, from: f32, to: f32) -> impl Iterator<Item = (Gap, Gap)> {
+            let (start, end) = (self.distance(from), self.distance(to));
+            self.pipes
+                .iter()
+                .filter(move |pipe| pipe.touched < end && start < pipe.touched + OVERLAP)
+                .map(move |pipe| {
+                    let gap = |time: f32| pipe.behaviour.gap(pipe.placed, time - pipe.spawned);
+                    (gap(from), gap(to))
+                })
+        }
+    }
+
+    /// The bird's vertical state in the search.
+    #[derive(Clone, Copy)]
+    struct Flight {
+        y: f32,
+        velocity: f32,
+        cooldown: f32,
+    }
+
+    impl Flight {
+        /// Where the flight will be once it has moved a cell's height at a cell's speed.
+        fn ahead(&self) -> f32 {
+            self.y + self.velocity * CELL_HEIGHT / CELL_SPEED
+        }
+    }
+
+    /// Returns whether some sequence of flaps flies a bird with the preset's physics
+    /// through all the pipes of the run, with their gaps where their behaviour has them
+    /// at the time.
+    /// The search starts as the bird reaches the first pipe, which spawned with no
+    /// constraint and far enough off for the bird to get anywhere, so the bird starts in
+    /// the middle of its gap, anywhere between just having flapped and the top of its climb.
+    /// Only one flight is kept per cell of a grid over height, speed and cooldown, and
+    /// every flight kept is one the bird can really fly.
+    fn solvable(preset: PhysicsPreset, run: &Run) -> bool {
+        let physics = preset.physics();
+        // The slowest rate the game runs at, so the search has the fewest steps to try.
+        let dt = TickRate::Hz60.dt();
+        // Collisions are checked over the whole tick, the bird and the gaps both move evenly
+        // through it, so the bird gets through a tick if it is clear at both ends.
+        let survives = |before: f32, after: f32, gaps: &[(Gap, Gap)]| {
+            let within =
+                |y: f32, top: f32, bottom: f32| y >= top && y + BIRD_HEIGHT as f32 <= bottom;
+            within(after, 0.0, GROUND_Y as f32)
+                && gaps.iter().all(|(start, end)| {
+                    within(before, start.top, start.bottom())
+                        && within(after, end.top, end.bottom())
+                })
+        };
+
+        let cells = (GROUND_Y as f32 / CELL_HEIGHT) as usize + 1;
+        // The highest and lowest flight kept in each cell, as indices into the next flights.
+        let mut kept: Vec<Option<[usize; 2]>> = vec![None; cells * SPEED_CELLS * 2];
+        let flap_velocity = physics.flap(0.0);
+        let start = run.time(run.pipes[0].touched);
+        let first = run.pipes[0].placed;
+        let mut flights: Vec<Flight> = (0..=(-flap_velocity / CELL_SPEED) as u32)
+            .map(|i| Flight {
+                y: first.top + (first.size - BIRD_HEIGHT as f32) / 2.0,
+                velocity: flap_velocity + i as f32 * CELL_SPEED,
+                cooldown: 0.0,
+            })
+            .collect();
+        let end = run.time(run.pipes.last().unwrap().touched + OVERLAP);
+        let mut time = start;
+
+        while time < end {
+            time += dt;
+            let gaps: Vec<_> = run.gaps(time - dt, time).collect();
+            let (mut next, mut filled) = (Vec::new(), Vec::new());
+            // Flapping during the cooldown does nothing, so only flights past it branch.
+            let branches = flights.iter().flat_map(|f| {
+                let flaps: &[bool] = if f.cooldown > 0.0 {
+                    &[false]
+                } else {
+                    &[false, true]
+                };
+                flaps.iter().map(move |&flap| (f, flap))
+            });
+            for (flight, flap) in branches {
+                let (mut flight, before) = (*flight, flight.y);
+                if flap {
+                    flight.velocity = physics.flap(flight.velocity);
+                    flight.cooldown = physics.flap_cooldown;
+                }
+                flight.cooldown = (flight.cooldown - dt).max(0.0);
+                let (velocity, distance) = physics.step(flight.velocity, dt);
+                flight.velocity = velocity;
+                flight.y += distance;
+
+                if !survives(before, flight.y, &gaps) {
+                    continue;
+                }
+                let row = (flight.y / CELL_HEIGHT) as usize;
+                let column = ((flight.velocity / CELL_SPEED) as i32 + SPEED_CELLS as i32 / 2)
+                    .clamp(0, SPEED_CELLS as i32 - 1) as usize;
+                let cell = ((row * SPEED_CELLS + column) * 2) + (flight.cooldown > 0.0) as usize;
+                // Keeping both ends of the cell stops the search drifting to one side of
+                // it, which loses flights that only just make it. Ends are where the flights
+                // are about to be, so both height and speed count.
+                match &mut kept[cell] {
+                    None => {
+                        kept[cell] = Some([next.len(); 2]);
+                        filled.push(cell);
+                        next.push(flight);
+                    }
+                    Some(ends) => {
+                        let [highest, lowest] = *ends;
+                        let end = if flight.ahead() < next[highest].ahead() {
+                            0
+                        } else if flight.ahead() > next[lowest].ahead() {
+                            1
+                        } else {
+                            continue;
+                        };
+                        if highest == lowest {
+                            ends[end] = next.len();
+                            next.push(flight);
+                        } else {
+                            next[ends[end]] = flight;
+                        }
+                    }
+                }
+            }
+
+            for cell in filled {
+                kept[cell] = None;
+            }
+            flights = next;
+            if flights.is_empty() {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Flies runs of `pipes` pipes on every seed, for every preset and difficulty and
+    /// across every stage change, which happens halfway through.
+    /// Each setup flies on its own thread.
+    fn fly_runs(seeds: Range<u64>, pipes: usize) -> () {
+        thread::scope(|scope| {
+            for preset in [
+                PhysicsPreset::Classic,
+                PhysicsPreset::Floaty,
+                PhysicsPreset::Heavy,
+            ] {
+                for difficulty in Difficulty::ALL {
+                    for stage in 0..DIFFICULTY_CURVE.len() - 1 {
+                        let seeds = seeds.clone();
+                        scope.spawn(move || {
+                            for seed in seeds {
+                                let run = Run::generate(preset, difficulty, seed, stage, pipes);
+                                let gaps: Vec<_> =
+                                    run.pipes.iter().map(|pipe| pipe.placed).collect();
+                                assert!(
+                                    solvable(preset, &run),
+                                    "{preset} {difficulty} stage {stage} seed {seed}: {gaps:?}"
+                                );
+                            }
+                        });
+                    }
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn every_generated_sequence_can_be_flown() {
+        fly_runs(0..1, 10);
+    }
+
+    /// Two hundred seeds of long runs for every setup, a couple of hours on one core and
+    /// too slow for every test run: `cargo test --release -- --ignored every_generated`.
+    #[test]
+    #[ignore]
+    fn every_generated_sequence_can_be_flown_over_many_seeds() {
+        fly_runs(0..200, 40);
+    }
+
+    #[test]
+    fn envelope_keeps_gaps_within_reach() {
+        let envelope = Envelope {
+            climb: 50.0,
+            drop: 80.0,
+        };
+        let previous = Gap {
+            top: 200.0,
+            size: 120.0,
+        };
+
+        let tops = envelope.tops_after(previous, 0.0, 100, 0.0);
+        assert_eq!(tops, 150.0..300.0);
+        // Room is left for both gaps to move.
+        let tops = envelope.tops_after(previous, 10.0, 100, 20.0);
+        assert_eq!(tops, 180.0..270.0);
+    }
+}
@@ -0,0 +1,47 @@
+use crate::config::{
+    FINISH_LINE_DARK_COLOR, FINISH_LINE_LIGHT_COLOR, FINISH_LINE_SQUARE, FINISH_LINE_WIDTH,
+    GROUND_Y,
+};
+use crate::game::draw_utils::draw_pixel;
+
+/// The line a level ends at, from the top of the playfield down to the ground.
+pub struct FinishLine {
+    pub x: f32,
+    /// Left edge before the last update, used to interpolate rendering between updates.
+    previous_x: f32,
+    speed: f32,
+}
+
+impl FinishLine {
+    pub fn new(x: f32, speed: f32) -> Self {
+        Self {
+            x,
+            previous_x: x,
+            speed,
+        }
+    }
+
+    pub fn update(&mut self, dt: f32) -> () {
+        self.previous_x = self.x;
+        self.x -= self.speed * dt;
+    }
+
+    /// Draws the chequered line `alpha` of the way from its previous position to its
+    /// current one.
+    pub fn draw(&self, frame: &mut [u8], alpha: f32) -> () {
+        let left = (self.previous_x + (self.x - self.previous_x) * alpha) as i32;
+
+        for y in 0..GROUND_Y {
+            for column in 0..FINISH_LINE_WIDTH {
+                let light =
+                    (column / FINISH_LINE_SQUARE + y / FINISH_LINE_SQUARE).is_multiple_of(2);
+                let color = if light {
+                    FINISH_LINE_LIGHT_COLOR
+                } else {
+                    FINISH_LINE_DARK_COLOR
+                };
+                draw_pixel(frame, left + column as i32, y as i32, color);
+            }
+        }
+    }
+}
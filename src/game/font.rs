@@ -0,0 +1,440 @@
+use crate::config::{
+    ACUTE, CEDILLA, CIRCUMFLEX, DIAERESIS, DIGITS, FONT_HEIGHT_PIXEL_MAP_PIXELS,
+    FONT_WIDTH_SIZE_PIXEL_MAP_PIXELS, GRAVE, LETTERS, MISSING_GLYPH, SPECIAL_CHARS, SPECIALS,
+    TILDE,
+};
+use crate::game::draw_utils::draw_pixel;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+static FONT: OnceLock<Font> = OnceLock::new();
+
+/// Where an accent goes relative to the letter it is combined with.
+enum Mark {
+    Above(&'static [bool; 14]),
+    Below(&'static [bool; 14]),
+}
+
+/// Accented letters the builtin font builds from a letter and a mark, enough for French
+/// and Spanish.
+const ACCENTED_LETTERS: [(char, char, Mark); 22] = [
+    ('à', 'a', Mark::Above(&GRAVE)),
+    ('â', 'a', Mark::Above(&CIRCUMFLEX)),
+    ('ä', 'a', Mark::Above(&DIAERESIS)),
+    ('á', 'a', Mark::Above(&ACUTE)),
+    ('ç', 'c', Mark::Below(&CEDILLA)),
+    ('è', 'e', Mark::Above(&GRAVE)),
+    ('é', 'e', Mark::Above(&ACUTE)),
+    ('ê', 'e', Mark::Above(&CIRCUMFLEX)),
+    ('ë', 'e', Mark::Above(&DIAERESIS)),
+    ('í', 'i', Mark::Above(&ACUTE)),
+    ('î', 'i', Mark::Above(&CIRCUMFLEX)),
+    ('ï', 'i', Mark::Above(&DIAERESIS)),
+    ('ñ', 'n', Mark::Above(&TILDE)),
+    ('ó', 'o', Mark::Above(&ACUTE)),
+    ('ô', 'o', Mark::Above(&CIRCUMFLEX)),
+    ('ö', 'o', Mark::Above(&DIAERESIS)),
+    ('ú', 'u', Mark::Above(&ACUTE)),
+    ('ù', 'u', Mark::Above(&GRAVE)),
+    ('û', 'u', Mark::Above(&CIRCUMFLEX)),
+    ('ü', 'u', Mark::Above(&DIAERESIS)),
+    ('ÿ', 'y', Mark::Above(&DIAERESIS)),
+    ('ý', 'y', Mark::Above(&ACUTE)),
+];
+
+/// Punctuation the builtin font draws as another character upside down.
+const INVERTED: [(char, char); 2] = [('¡', '!'), ('¿', '?')];
+
+/// Replaces the builtin font for all text drawn from now on.
+/// Only works before any text is drawn, otherwise the font is handed back.
+pub fn set_font(font: Font) -> Result<(), Font> {
+    FONT.set(font)
+}
+
+/// The font all text is drawn with.
+pub fn font() -> &'static Font {
+    FONT.get_or_init(Font::builtin)
+}
+
+/// One character's bitmap, measured in font pixels.
+pub struct Glyph {
+    width: u32,
+    height: u32,
+    /// Where the bitmap's top left corner sits relative to the pen, with y down from the
+    /// top of the line.
+    left: i32,
+    top: i32,
+    /// How far the pen moves after drawing the glyph.
+    pub advance: u32,
+    /// Row-major, `width * height` long.
+    bits: Vec<bool>,
+}
+
+impl Glyph {
+    /// A glyph filling a whole cell of the builtin font.
+    fn from_cell(pixel_map: &[bool; 63]) -> Self {
+        Self {
+            width: FONT_WIDTH_SIZE_PIXEL_MAP_PIXELS,
+            height: FONT_HEIGHT_PIXEL_MAP_PIXELS,
+            left: 0,
+            top: 0,
+            advance: FONT_WIDTH_SIZE_PIXEL_MAP_PIXELS,
+            bits: pixel_map.to_vec(),
+        }
+    }
+
+    /// A builtin cell with a mark two rows above or below it.
+    fn accented(pixel_map: &[bool; 63], mark: &Mark) -> Self {
+        let blank = [false; 14];
+        let (above, below) = match mark {
+            Mark::Above(mark) => (*mark, &blank),
+            Mark::Below(mark) => (&blank, *mark),
+        };
+
+        let mut glyph = Self::from_cell(pixel_map);
+        glyph.bits = [&above[..], pixel_map, &below[..]].concat();
+        glyph.height += 4;
+        glyph.top = -2;
+        glyph
+    }
+
+    /// A builtin cell flipped upside down.
+    fn inverted(pixel_map: &[bool; 63]) -> Self {
+        let mut glyph = Self::from_cell(pixel_map);
+        glyph.bits = pixel_map
+            .chunks(FONT_WIDTH_SIZE_PIXEL_MAP_PIXELS as usize)
+            .rev()
+            .flatten()
+            .copied()
+            .collect();
+        glyph
+    }
+
+    /// Draws the glyph with the pen at (x, y), the top left of the line, each font pixel
+    /// `pixel_size` screen pixels wide. Clipped, so text running off the screen doesn't
+    /// wrap or panic.
+    fn draw(&self, frame: &mut [u8], x: i32, y: i32, pixel_size: u32, color: [u8; 4]) -> () {
+        let size = pixel_size as i32;
+
+        for j in 0..self.height {
+            for i in 0..self.width {
+                if !self.bits[(j * self.width + i) as usize] {
+                    continue;
+                }
+
+                let pixel_x = x + (self.left + i as i32) * size;
+                let pixel_y = y + (self.top + j as i32) * size;
+                for dy in 0..size {
+                    for dx in 0..size {
+                        draw_pixel(frame, pixel_x + dx, pixel_y + dy, color);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A bitmap font with glyphs of any size and their own advance widths.
+pub struct Font {
+    glyphs: HashMap<char, Glyph>,
+    /// Drawn for characters the font has no glyph for.
+    missing: Glyph,
+    line_height: u32,
+}
+
+impl Font {
+    /// The fixed width 7x9 font from the config's pixel maps, with accented letters built
+    /// from them.
+    pub fn builtin() -> Self {
+        let mut glyphs = HashMap::new();
+        for (c, pixel_map) in ('0'..='9').zip(DIGITS.iter()) {
+            glyphs.insert(c, Glyph::from_cell(pixel_map));
+        }
+        for (c, pixel_map) in ('a'..='z').zip(LETTERS.iter()) {
+            glyphs.insert(c, Glyph::from_cell(pixel_map));
+        }
+        for (c, pixel_map) in SPECIAL_CHARS.chars().zip(SPECIALS.iter()) {
+            glyphs.insert(c, Glyph::from_cell(pixel_map));
+        }
+        glyphs.insert(' ', Glyph::from_cell(&[false; 63]));
+
+        for (c, letter, mark) in &ACCENTED_LETTERS {
+            let pixel_map = &LETTERS[(*letter as u8 - b'a') as usize];
+            glyphs.insert(*c, Glyph::accented(pixel_map, mark));
+        }
+        for (c, upright) in INVERTED {
+            let pixel_map = &SPECIALS[SPECIAL_CHARS.find(upright).unwrap()];
+            glyphs.insert(c, Glyph::inverted(pixel_map));
+        }
+
+        Self {
+            glyphs,
+            missing: Glyph::from_cell(&MISSING_GLYPH),
+            line_height: FONT_HEIGHT_PIXEL_MAP_PIXELS,
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        Self::parse_bdf(&contents)
+    }
+
+    /// Parses a font in the Glyph Bitmap Distribution Format.
+    /// The line height is the font's ascent plus descent, and the glyph named by
+    /// DEFAULT_CHAR, if any, replaces the builtin missing glyph.
+    pub fn parse_bdf(contents: &str) -> Result<Self, String> {
+        let mut lines = contents.lines().enumerate().map(|(i, line)| (i + 1, line));
+        let mut bounding_box: Option<[i32; 4]> = None;
+        let (mut ascent, mut descent, mut default_char) = (None, None, None);
+        let mut glyphs = HashMap::new();
+
+        while let Some((number, line)) = lines.next() {
+            let mut words = line.split_whitespace();
+            match words.next() {
+                Some("FONTBOUNDINGBOX") => bounding_box = Some(bdf_numbers(number, words)?),
+                Some("FONT_ASCENT") => ascent = Some(bdf_numbers::<1>(number, words)?[0]),
+                Some("FONT_DESCENT") => descent = Some(bdf_numbers::<1>(number, words)?[0]),
+                Some("DEFAULT_CHAR") => default_char = Some(bdf_numbers::<1>(number, words)?[0]),
+                Some("STARTCHAR") => {
+                    let [_, box_height, _, box_y] = bounding_box
+                        .ok_or(format!("line {number}: STARTCHAR before FONTBOUNDINGBOX"))?;
+                    let ascent = ascent.unwrap_or(box_height + box_y);
+                    let (encoding, glyph) = parse_bdf_glyph(&mut lines, ascent)?;
+                    // Glyphs outside the standard encoding are -1.
+                    if let Some(c) = u32::try_from(encoding).ok().and_then(char::from_u32) {
+                        glyphs.insert(c, glyph);
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        let [_, box_height, _, _] = bounding_box.ok_or("missing FONTBOUNDINGBOX")?;
+        let line_height = match (ascent, descent) {
+            (Some(ascent), Some(descent)) => ascent + descent,
+            _ => box_height,
+        };
+        let missing = default_char
+            .and_then(|c| u32::try_from(c).ok().and_then(char::from_u32))
+            .and_then(|c| glyphs.remove(&c))
+            .unwrap_or_else(|| Glyph::from_cell(&MISSING_GLYPH));
+
+        Ok(Self {
+            glyphs,
+            missing,
+            line_height: line_height.max(1) as u32,
+        })
+    }
+
+    /// Returns the glyph for the character, letters fall back to the other case.
+    pub fn glyph(&self, c: char) -> Option<&Glyph> {
+        let other_case = |cased: Option<char>| cased.and_then(|cased| self.glyphs.get(&cased));
+
+        self.glyphs
+            .get(&c)
+            .or_else(|| other_case(c.to_lowercase().next()))
+            .or_else(|| other_case(c.to_uppercase().next()))
+    }
+
+    /// Height of a line of text in font pixels.
+    pub fn line_height(&self) -> u32 {
+        self.line_height
+    }
+
+    /// Width of the string in font pixels.
+    pub fn text_width(&self, string: &str) -> u32 {
+        string
+            .chars()
+            .map(|c| self.glyph(c).unwrap_or(&self.missing).advance)
+            .sum()
+    }
+
+    /// Draws a single line with its top left at (x, y), each font pixel `pixel_size` screen
+    /// pixels wide.
+    pub fn draw_line(
+        &self,
+        frame: &mut [u8],
+        string: &str,
+        x: i32,
+        y: i32,
+        pixel_size: u32,
+        color: [u8; 4],
+    ) -> () {
+        let mut x = x;
+        for c in string.chars() {
+            let glyph = self.glyph(c).unwrap_or(&self.missing);
+            glyph.draw(frame, x, y, pixel_size, color);
+            x += (glyph.advance * pixel_size) as i32;
+        }
+    }
+}
+
+/// Parses the properties of one glyph up to its ENDCHAR, returning its encoding.
+fn parse_bdf_glyph<'a>(
+    lines: &mut impl Iterator<Item = (usize, &'a str)>,
+    ascent: i32,
+) -> Result<(i32, Glyph), String> {
+    let (mut encoding, mut advance, mut bbx) = (None, None, None);
+
+    while let Some((number, line)) = lines.next() {
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("ENCODING") => encoding = Some(bdf_numbers::<1>(number, words)?[0]),
+            Some("DWIDTH") => advance = Some(bdf_numbers::<2>(number, words)?[0]),
+            Some("BBX") => bbx = Some(bdf_numbers::<4>(number, words)?),
+            Some("BITMAP") => {
+                let encoding = encoding.ok_or(format!("line {number}: BITMAP before ENCODING"))?;
+                let [width, height, x, y] =
+                    bbx.ok_or(format!("line {number}: BITMAP before BBX"))?;
+                if width < 0 || height < 0 {
+                    return Err(format!("line {number}: negative glyph size"));
+                }
+
+                let mut bits = Vec::with_capacity((width * height) as usize);
+                for _ in 0..height {
+                    let (number, row) = lines
+                        .next()
+                        .ok_or(format!("line {number}: bitmap ends early"))?;
+                    let row_bits = row.trim().chars().flat_map(|digit| {
+                        let value = digit.to_digit(16);
+                        (0..4)
+                            .rev()
+                            .map(move |bit| value.map(|value| value >> bit & 1 == 1))
+                    });
+                    let row_bits: Option<Vec<bool>> = row_bits.take(width as usize).collect();
+                    match row_bits {
+                        Some(row_bits) if row_bits.len() == width as usize => bits.extend(row_bits),
+                        _ => return Err(format!("line {number}: invalid bitmap row `{row}`")),
+                    }
+                }
+
+                match lines.next() {
+                    Some((_, line)) if line.trim() == "ENDCHAR" => (),
+                    _ => return Err(format!("line {number}: bitmap rows don't match BBX")),
+                }
+
+                let glyph = Glyph {
+                    width: width as u32,
+                    height: height as u32,
+                    left: x,
+                    // BDF measures up from the baseline, the bitmap's bottom row is at y.
+                    top: ascent - (y + height),
+                    advance: advance.unwrap_or(width).max(0) as u32,
+                    bits,
+                };
+                return Ok((encoding, glyph));
+            }
+            _ => (),
+        }
+    }
+
+    Err("glyph without a BITMAP".to_string())
+}
+
+fn bdf_numbers<'a, const N: usize>(
+    number: usize,
+    words: impl Iterator<Item = &'a str>,
+) -> Result<[i32; N], String> {
+    let values = words
+        .map(|word| word.parse())
+        .collect::<Result<Vec<i32>, _>>()
+        .map_err(|_| format!("line {number}: expected numbers"))?;
+
+    values
+        .get(..N)
+        .and_then(|values| values.try_into().ok())
+        .ok_or(format!("line {number}: expected {N} numbers"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Font;
+    use crate::config::{HEIGHT, WIDTH};
+
+    const TINY: &str = "STARTFONT 2.1
+FONT tiny
+SIZE 4 75 75
+FONTBOUNDINGBOX 3 5 0 -1
+STARTPROPERTIES 2
+FONT_ASCENT 4
+FONT_DESCENT 1
+ENDPROPERTIES
+CHARS 2
+STARTCHAR i
+ENCODING 105
+DWIDTH 2 0
+BBX 1 4 0 0
+BITMAP
+80
+00
+80
+80
+ENDCHAR
+STARTCHAR m
+ENCODING 109
+DWIDTH 4 0
+BBX 3 2 0 0
+BITMAP
+E0
+A0
+ENDCHAR
+ENDFONT
+";
+
+    fn lit_pixels(font: &Font, string: &str) -> Vec<(usize, usize)> {
+        let mut frame = vec![0; (WIDTH * HEIGHT * 4) as usize];
+        font.draw_line(&mut frame, string, 0, 0, 1, [0xFF; 4]);
+        frame
+            .chunks_exact(4)
+            .enumerate()
+            .filter(|(_, pixel)| *pixel != [0; 4])
+            .map(|(i, _)| (i % WIDTH as usize, i / WIDTH as usize))
+            .collect()
+    }
+
+    #[test]
+    fn parses_bdf_glyphs_with_their_own_widths() {
+        let font = Font::parse_bdf(TINY).unwrap();
+
+        assert_eq!(font.line_height(), 5);
+        assert_eq!(font.text_width("im"), 6);
+        // Uppercase falls back to the lowercase glyph.
+        assert_eq!(font.text_width("I"), 2);
+
+        // The i's dot sits on the top row, the m's bottom row on the baseline.
+        assert_eq!(lit_pixels(&font, "i"), vec![(0, 0), (0, 2), (0, 3)]);
+        assert_eq!(
+            lit_pixels(&font, "im"),
+            vec![
+                (0, 0),
+                (0, 2),
+                (2, 2),
+                (3, 2),
+                (4, 2),
+                (0, 3),
+                (2, 3),
+                (4, 3)
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_broken_bitmaps() {
+        assert!(Font::parse_bdf(&TINY.replace("A0", "Z0")).is_err());
+        assert!(Font::parse_bdf(&TINY.replace("E0\nA0\n", "E0\n")).is_err());
+        assert!(Font::parse_bdf("STARTFONT 2.1\nENDFONT\n").is_err());
+    }
+
+    #[test]
+    fn bundled_font_covers_ascii_and_accents() {
+        let font = Font::parse_bdf(include_str!("../../assets/fonts/proportional.bdf")).unwrap();
+
+        for c in (' '..='~').chain("àçéñü¿¡".chars()) {
+            assert!(font.glyph(c).is_some(), "{c:?} has no glyph");
+        }
+        assert!(font.text_width("i") < font.text_width("m"));
+        assert_eq!(font.line_height(), Font::builtin().line_height());
+    }
+}
@@ -0,0 +1,310 @@
+//! Golden-image tests for `Game::draw`.
+//!
+//! Each test renders a game state into an offscreen frame and compares it against a
+//! reference PNG in `tests/golden`. On a mismatch the actual frame and a diff image
+//! are written to `target/golden-diff` so the regression can be inspected.
+//!
+//! Run with `UPDATE_GOLDEN=1 cargo test` to (re)generate the reference images after an
+//! intentional rendering change.
+
+use super::{Game, GameState, HighScores, Language, ScorePosition, Theme};
+use crate::config::{DEFAULT_TICK_RATE, HEIGHT, WIDTH};
+use crate::settings::Settings;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+const SEED: u64 = 1234;
+
+/// Maximum difference allowed per colour channel before a pixel counts as different.
+const CHANNEL_TOLERANCE: u8 = 2;
+
+#[test]
+fn main_menu_matches_golden() {
+    let game = Game::with_seed(SEED);
+
+    assert_matches_golden("main_menu", &render(&game));
+}
+
+#[test]
+fn high_scores_match_golden() {
+    let mut high_scores = HighScores::default();
+    for score in [4, 17, 9] {
+        high_scores.record(score);
+    }
+    let mut game = Game::with_seed(SEED).with_high_scores(high_scores);
+    game.down_key_hit();
+    game.down_key_hit();
+    game.enter_key_hit();
+
+    assert_matches_golden("high_scores", &render(&game));
+}
+
+#[test]
+fn night_settings_match_golden() {
+    let settings = Settings {
+        theme: Theme::Night,
+        show_fps: true,
+        ..Settings::default()
+    };
+    let mut game = Game::with_seed(SEED).with_settings(settings);
+    game.up_key_hit();
+    game.up_key_hit();
+    game.enter_key_hit();
+    game.down_key_hit();
+    game.enter_key_hit();
+
+    assert_matches_golden("settings_night", &render(&game));
+}
+
+#[test]
+fn mid_play_matches_golden() {
+    let mut game = Game::with_seed(SEED);
+    game.start_run();
+
+    // Keep the bird hovering while the first pipes scroll in.
+    hover(&mut game, 360);
+    assert!(game.game_state == GameState::Playing);
+
+    assert_matches_golden("mid_play", &render(&game));
+}
+
+#[test]
+fn debug_overlay_matches_golden() {
+    let mut game = Game::with_seed(SEED);
+    game.start_run();
+    hover(&mut game, 360);
+    game.toggle_debug_overlay();
+
+    assert_matches_golden("debug_overlay", &render(&game));
+}
+
+#[test]
+fn score_pop_matches_golden() {
+    let mut high_scores = HighScores::default();
+    high_scores.record(12);
+    let mut game = Game::with_seed(SEED).with_high_scores(high_scores);
+    game.start_run();
+    hover(&mut game, 360);
+
+    // Right after a point the score is at its biggest.
+    game.score.increase_score();
+
+    assert_matches_golden("score_pop", &render(&game));
+}
+
+#[test]
+fn progressive_stage_matches_golden() {
+    let mut game = Game::with_seed(SEED)
+        .with_progressive(true)
+        .with_score_position(ScorePosition::TopLeft);
+    game.start_run();
+    hover(&mut game, 360);
+
+    // Reaching ten points moves the run onto the second stage.
+    game.score.score = 10;
+    game.update(DEFAULT_TICK_RATE.dt());
+
+    assert_matches_golden("progressive_stage", &render(&game));
+}
+
+#[test]
+fn console_matches_golden() {
+    let mut game = Game::with_seed(SEED);
+    game.start_run();
+    game.console().toggle();
+    for line in ["god on", "score 50", "tick 600"] {
+        game.console().type_text(line);
+        game.submit_console();
+    }
+    game.console().type_text("spawn_pipe");
+
+    assert_matches_golden("console", &render(&game));
+}
+
+#[test]
+fn death_screen_matches_golden() {
+    let game = die(Game::with_seed(SEED));
+
+    assert_matches_golden("death_screen", &render(&game));
+}
+
+#[test]
+fn spanish_death_screen_matches_golden() {
+    let game = die(Game::with_seed(SEED).with_language(Language::Spanish));
+
+    assert_matches_golden("death_screen_es", &render(&game));
+}
+
+#[test]
+fn levels_match_golden() {
+    let mut high_scores = HighScores::default();
+    high_scores.record_level("first flight", 14);
+    let mut game = Game::with_seed(SEED).with_high_scores(high_scores);
+    for _ in 0..3 {
+        game.down_key_hit();
+    }
+    game.enter_key_hit();
+
+    assert_matches_golden("levels", &render(&game));
+}
+
+#[test]
+fn level_complete_matches_golden() {
+    let mut game = Game::with_seed(SEED);
+    for _ in 0..3 {
+        game.down_key_hit();
+    }
+    game.enter_key_hit();
+    game.enter_key_hit();
+
+    // Hover through the first level, nothing in the way counts.
+    game.god_mode = true;
+    for _ in 0..100_000 {
+        if !game.is_playing() {
+            break;
+        }
+        if game.bird.position.y > 250.0 {
+            game.space_bar_hit();
+        }
+        game.update(DEFAULT_TICK_RATE.dt());
+    }
+    assert!(game.game_state == GameState::LevelComplete);
+
+    assert_matches_golden("level_complete", &render(&game));
+}
+
+#[test]
+fn interpolation_starts_at_the_previous_update() {
+    let mut game = Game::with_seed(SEED);
+    game.start_run();
+    game.space_bar_hit();
+    for _ in 0..300 {
+        game.update(DEFAULT_TICK_RATE.dt());
+    }
+    let before = render(&game);
+
+    game.update(DEFAULT_TICK_RATE.dt());
+    let mut frame = vec![0; (WIDTH * HEIGHT * 4) as usize];
+    game.draw(&mut frame, 0.0);
+
+    assert!(frame == before);
+    assert!(frame != render(&game));
+}
+
+/// Starts a run and, without input, lets the bird fall to the floor.
+fn die(mut game: Game) -> Game {
+    game.start_run();
+    for _ in 0..10_000 {
+        if game.game_state == GameState::Dead {
+            break;
+        }
+        game.update(DEFAULT_TICK_RATE.dt());
+    }
+    assert!(game.game_state == GameState::Dead);
+    game
+}
+
+/// Keeps the bird flapping around the same height for `ticks` updates.
+fn hover(game: &mut Game, ticks: u32) -> () {
+    for _ in 0..ticks {
+        if game.bird.position.y > 250.0 {
+            game.space_bar_hit();
+        }
+        game.update(DEFAULT_TICK_RATE.dt());
+    }
+}
+
+/// Renders the latest state of the game into a freshly cleared frame.
+fn render(game: &Game) -> Vec<u8> {
+    let mut frame = vec![0; (WIDTH * HEIGHT * 4) as usize];
+    game.draw(&mut frame, 1.0);
+    frame
+}
+
+fn assert_matches_golden(name: &str, frame: &[u8]) -> () {
+    let golden_path = golden_dir().join(format!("{name}.png"));
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        write_png(&golden_path, frame);
+        return;
+    }
+
+    let expected = read_png(&golden_path).unwrap_or_else(|| {
+        panic!(
+            "missing golden image {}, run with UPDATE_GOLDEN=1 to create it",
+            golden_path.display()
+        )
+    });
+
+    let mut diff = vec![0; frame.len()];
+    let mut differing_pixels = 0;
+    for ((actual, expected), diff) in frame
+        .chunks_exact(4)
+        .zip(expected.chunks_exact(4))
+        .zip(diff.chunks_exact_mut(4))
+    {
+        let differs = actual
+            .iter()
+            .zip(expected)
+            .any(|(a, e)| a.abs_diff(*e) > CHANNEL_TOLERANCE);
+
+        if differs {
+            differing_pixels += 1;
+            diff.copy_from_slice(&[0xFF, 0x00, 0x00, 0xFF]);
+        } else {
+            // Dim the matching pixels so the differences stand out.
+            let luma = ((expected[0] as u32 + expected[1] as u32 + expected[2] as u32) / 12) as u8;
+            diff.copy_from_slice(&[luma, luma, luma, 0xFF]);
+        }
+    }
+
+    if differing_pixels == 0 {
+        return;
+    }
+
+    let out_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("target/golden-diff");
+    fs::create_dir_all(&out_dir).unwrap();
+    write_png(&out_dir.join(format!("{name}.actual.png")), frame);
+    write_png(&out_dir.join(format!("{name}.diff.png")), &diff);
+
+    panic!(
+        "{name}: {differing_pixels} pixels differ from {}, see {}",
+        golden_path.display(),
+        out_dir.display()
+    );
+}
+
+fn golden_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden")
+}
+
+fn read_png(path: &Path) -> Option<Vec<u8>> {
+    let decoder = png::Decoder::new(File::open(path).ok()?);
+    let mut reader = decoder.read_info().unwrap();
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buf).unwrap();
+
+    assert_eq!(
+        (info.width, info.height, info.color_type),
+        (WIDTH, HEIGHT, png::ColorType::Rgba),
+        "golden image {} has the wrong format",
+        path.display()
+    );
+
+    buf.truncate(info.buffer_size());
+    Some(buf)
+}
+
+fn write_png(path: &Path, frame: &[u8]) -> () {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+
+    let mut encoder = png::Encoder::new(BufWriter::new(File::create(path).unwrap()), WIDTH, HEIGHT);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .unwrap()
+        .write_image_data(frame)
+        .unwrap();
+}
@@ -0,0 +1,120 @@
+use crate::config::{
+    GROUND_A_COLOR, GROUND_DARK_B_COLOR, GROUND_DARK_G_COLOR, GROUND_DARK_R_COLOR,
+    GROUND_EDGE_B_COLOR, GROUND_EDGE_G_COLOR, GROUND_EDGE_HEIGHT, GROUND_EDGE_R_COLOR,
+    GROUND_LIGHT_B_COLOR, GROUND_LIGHT_G_COLOR, GROUND_LIGHT_R_COLOR, GROUND_STRIPE_WIDTH,
+    GROUND_Y, HEIGHT, WIDTH,
+};
+use crate::game::collision_box::CollisionBox;
+use crate::game::vector2::Vector2;
+
+/// The strip at the bottom of the playfield.
+/// It scrolls with the pipes so the world looks like it moves past the bird.
+pub struct Ground {
+    pub collision_box: CollisionBox,
+    /// How far the stripe pattern has scrolled, wrapped to one pattern period.
+    scroll: f32,
+    /// Scroll before the last update, used to interpolate rendering between updates.
+    previous_scroll: f32,
+}
+
+impl Ground {
+    pub fn new() -> Self {
+        Self {
+            collision_box: CollisionBox::new(
+                Vector2::new(0.0, GROUND_Y as f32),
+                Vector2::new(WIDTH as f32, HEIGHT as f32),
+            ),
+            scroll: 0.0,
+            previous_scroll: 0.0,
+        }
+    }
+
+    /// Returns the y of the ground's surface.
+    pub fn top(&self) -> f32 {
+        self.collision_box.min.y
+    }
+
+    /// Scrolls the ground at `speed` pixels per second, the speed the pipes move at.
+    pub fn update(&mut self, dt: f32, speed: f32) -> () {
+        let period = (2 * GROUND_STRIPE_WIDTH) as f32;
+
+        self.previous_scroll = self.scroll;
+        self.scroll += speed * dt;
+
+        // Wrap both so interpolating between them never jumps a whole period.
+        if self.scroll >= period {
+            self.scroll -= period;
+            self.previous_scroll -= period;
+        }
+    }
+
+    /// Returns the scroll `alpha` of the way from the previous scroll to the current one.
+    /// The previous scroll is below zero right after a wrap, so the result is wrapped again.
+    fn scroll_at(&self, alpha: f32) -> u32 {
+        let period = (2 * GROUND_STRIPE_WIDTH) as f32;
+        let scroll = self.previous_scroll + (self.scroll - self.previous_scroll) * alpha;
+
+        scroll.rem_euclid(period) as u32
+    }
+
+    /// Draws the ground scrolled `alpha` of the way from its previous scroll to its current one.
+    #[allow(clippy::identity_op)]
+    pub fn draw(&self, frame: &mut [u8], alpha: f32) -> () {
+        let stride = WIDTH as usize * 4;
+        let scroll = self.scroll_at(alpha);
+
+        for y in GROUND_Y..HEIGHT {
+            let depth = y - GROUND_Y;
+
+            for x in 0..WIDTH {
+                let color = if depth < GROUND_EDGE_HEIGHT {
+                    [
+                        GROUND_EDGE_R_COLOR,
+                        GROUND_EDGE_G_COLOR,
+                        GROUND_EDGE_B_COLOR,
+                    ]
+                } else if ((x + scroll + depth) / GROUND_STRIPE_WIDTH).is_multiple_of(2) {
+                    [
+                        GROUND_LIGHT_R_COLOR,
+                        GROUND_LIGHT_G_COLOR,
+                        GROUND_LIGHT_B_COLOR,
+                    ]
+                } else {
+                    [
+                        GROUND_DARK_R_COLOR,
+                        GROUND_DARK_G_COLOR,
+                        GROUND_DARK_B_COLOR,
+                    ]
+                };
+
+                let idx = y as usize * stride + x as usize * 4;
+
+                frame[idx + 0] = color[0];
+                frame[idx + 1] = color[1];
+                frame[idx + 2] = color[2];
+                frame[idx + 3] = GROUND_A_COLOR;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Ground;
+    use crate::config::GROUND_STRIPE_WIDTH;
+
+    #[test]
+    fn interpolation_carries_on_across_the_wrap() {
+        let period = 2 * GROUND_STRIPE_WIDTH;
+        let mut ground = Ground::new();
+
+        ground.update(1.0, period as f32 - 2.0);
+        ground.update(1.0, 4.0);
+
+        // Two pixels short of the wrap, then two pixels past it.
+        assert_eq!(ground.scroll_at(0.0), period - 2);
+        assert_eq!(ground.scroll_at(0.25), period - 1);
+        assert_eq!(ground.scroll_at(0.75), 1);
+        assert_eq!(ground.scroll_at(1.0), 2);
+    }
+}
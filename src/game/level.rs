@@ -0,0 +1,242 @@
+use crate::config::{COIN_SIZE, GROUND_Y, PIPE_SPEED, PIPE_WIDTH};
+use crate::game::pipe_behaviour::{Gap, PipeBehaviour};
+use std::fs;
+use std::io::{Error, ErrorKind::InvalidData};
+use std::path::Path;
+
+/// A pipe of a level, with its left edge `x` pixels along the level.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LevelPipe {
+    pub x: f32,
+    pub gap: Gap,
+    pub behaviour: PipeBehaviour,
+}
+
+/// A coin of a level, with its top left corner `x` pixels along the level.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LevelCoin {
+    pub x: f32,
+    pub y: f32,
+}
+
+/// A hand-authored run, played instead of random pipes.
+/// Distances along the level start at the right edge of the screen when the run starts,
+/// everything scrolls in once the world has moved that far.
+///
+/// Stored on disk as plain text, one entry per line, `#` starting a comment:
+/// ```text
+/// name first flight
+/// speed 80
+/// pipe 100 200 150
+/// coin 100 300
+/// pipe 200 150 130 oscillating
+/// finish 250
+/// ```
+/// `pipe <spacing> <gap top> <gap size> [behaviour]` comes `spacing` pixels after the
+/// previous pipe (or the start), the behaviour is static, oscillating, breathing or sliding.
+/// `coin <x> <y>` is `x` pixels after the left of the previous pipe (or the start).
+/// `finish <spacing>` is the line to reach, `spacing` pixels after the previous pipe,
+/// nothing can come after it.
+/// The speed is in pixels per second and can be left out.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Level {
+    pub name: String,
+    pub speed: f32,
+    pub pipes: Vec<LevelPipe>,
+    pub coins: Vec<LevelCoin>,
+    pub finish: f32,
+}
+
+impl Level {
+    /// The levels that come with the game.
+    pub fn built_in() -> Vec<Level> {
+        [
+            include_str!("../../assets/levels/first_flight.level"),
+            include_str!("../../assets/levels/on_the_move.level"),
+        ]
+        .into_iter()
+        .map(|contents| Self::parse(contents).expect("built-in levels are valid"))
+        .collect()
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn parse(contents: &str) -> Result<Self, Error> {
+        let mut name = None;
+        let mut speed = PIPE_SPEED;
+        let mut pipes = Vec::new();
+        let mut coins = Vec::new();
+        let mut finish = None;
+        // Left and right edges of the previous pipe, the start before the first one.
+        let (mut previous_left, mut previous_right) = (0.0, 0.0);
+
+        for (line_number, line) in contents.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let invalid = || Error::new(InvalidData, format!("line {}: {line}", line_number + 1));
+            let (key, value) = line.split_once(' ').ok_or_else(invalid)?;
+            let value = value.trim();
+            let words: Vec<&str> = value.split_whitespace().collect();
+            let numbers = |words: &[&str]| {
+                words
+                    .iter()
+                    .map(|word| word.parse::<u32>().map(|n| n as f32))
+                    .collect::<Result<Vec<f32>, _>>()
+                    .map_err(|_| invalid())
+            };
+
+            match key {
+                "name" => name = Some(value.to_string()),
+                "speed" => {
+                    speed = value.parse().map_err(|_| invalid())?;
+                    if !speed.is_finite() || speed <= 0.0 {
+                        return Err(invalid());
+                    }
+                }
+                "pipe" | "coin" if finish.is_some() => return Err(invalid()),
+                "pipe" => {
+                    let (words, behaviour) = match words[..] {
+                        [_, _, _, behaviour] => {
+                            (&words[..3], behaviour.parse().map_err(|_| invalid())?)
+                        }
+                        _ => (&words[..], PipeBehaviour::Static),
+                    };
+                    let [spacing, top, size] = numbers(words)?[..] else {
+                        return Err(invalid());
+                    };
+                    let gap = Gap { top, size };
+                    // The gap has to stay in the playfield wherever its behaviour moves it.
+                    let reach = behaviour.reach();
+                    if size <= 0.0 || top < reach || gap.bottom() + reach > GROUND_Y as f32 {
+                        return Err(invalid());
+                    }
+
+                    previous_left = previous_right + spacing;
+                    previous_right = previous_left + PIPE_WIDTH as f32;
+                    pipes.push(LevelPipe {
+                        x: previous_left,
+                        gap,
+                        behaviour,
+                    });
+                }
+                "coin" => {
+                    let [x, y] = numbers(&words)?[..] else {
+                        return Err(invalid());
+                    };
+                    if y + COIN_SIZE as f32 > GROUND_Y as f32 {
+                        return Err(invalid());
+                    }
+                    coins.push(LevelCoin {
+                        x: previous_left + x,
+                        y,
+                    });
+                }
+                "finish" => {
+                    let [spacing] = numbers(&words)?[..] else {
+                        return Err(invalid());
+                    };
+                    finish = Some(previous_right + spacing);
+                }
+                _ => return Err(invalid()),
+            }
+        }
+
+        // Coins are spawned in order along the level.
+        coins.sort_by(|a: &LevelCoin, b: &LevelCoin| a.x.total_cmp(&b.x));
+
+        Ok(Self {
+            name: name.ok_or_else(|| Error::new(InvalidData, "level has no name"))?,
+            speed,
+            pipes,
+            coins,
+            finish: finish.ok_or_else(|| Error::new(InvalidData, "level has no finish"))?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Level, LevelCoin};
+    use crate::config::{OSCILLATING_PIPE, PIPE_SPEED, PIPE_WIDTH};
+    use crate::game::pipe_behaviour::{Gap, PipeBehaviour};
+
+    #[test]
+    fn parses_distances_along_the_level() {
+        let level = Level::parse(
+            "# A comment.\n\
+             name two pipes\n\
+             pipe 100 200 150\n\
+             coin 40 300 # In the middle of the gap.\n\
+             pipe 200 150 130 oscillating\n\
+             finish 250\n",
+        )
+        .unwrap();
+
+        assert_eq!(level.name, "two pipes");
+        assert_eq!(level.speed, PIPE_SPEED);
+        assert_eq!(level.pipes.len(), 2);
+        assert_eq!(level.pipes[0].x, 100.0);
+        assert_eq!(
+            level.pipes[0].gap,
+            Gap {
+                top: 200.0,
+                size: 150.0
+            }
+        );
+        assert_eq!(level.pipes[0].behaviour, PipeBehaviour::Static);
+        let second = 100.0 + PIPE_WIDTH as f32 + 200.0;
+        assert_eq!(level.pipes[1].x, second);
+        assert_eq!(level.pipes[1].behaviour, OSCILLATING_PIPE);
+        assert_eq!(level.coins, vec![LevelCoin { x: 140.0, y: 300.0 }]);
+        assert_eq!(level.finish, second + PIPE_WIDTH as f32 + 250.0);
+    }
+
+    #[test]
+    fn coins_are_in_order_along_the_level() {
+        let level = Level::parse(
+            "name coins\n\
+             coin 300 200\n\
+             pipe 100 200 150\n\
+             coin 10 300\n\
+             finish 250\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            level.coins,
+            vec![
+                LevelCoin { x: 110.0, y: 300.0 },
+                LevelCoin { x: 300.0, y: 200.0 }
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_levels() {
+        for contents in [
+            "pipe 100 200 150\nfinish 100\n",
+            "name no finish\npipe 100 200 150\n",
+            "name bad gap\npipe 100 500 150\nfinish 100\n",
+            "name bad behaviour\npipe 100 200 150 spinning\nfinish 100\n",
+            "name bad speed\nspeed -5\nfinish 100\n",
+            "name nan speed\nspeed NaN\nfinish 100\n",
+            "name infinite speed\nspeed inf\nfinish 100\n",
+            "name gap out of reach\npipe 100 20 150 oscillating\nfinish 100\n",
+            "name pipe after finish\nfinish 100\npipe 100 200 150\n",
+            "name coin after finish\nfinish 100\ncoin 10 200\n",
+            "name unknown\nwall 100\nfinish 100\n",
+        ] {
+            assert!(Level::parse(contents).is_err(), "{contents}");
+        }
+    }
+
+    #[test]
+    fn built_in_levels_parse() {
+        assert!(!Level::built_in().is_empty());
+    }
+}
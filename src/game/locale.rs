@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// A language the UI text is translated to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Language {
+    English,
+    French,
+    Spanish,
+}
+
+impl Language {
+    /// The language's string table, every message the game shows.
+    fn table(self) -> &'static str {
+        match self {
+            Language::English => include_str!("../../assets/lang/en.lang"),
+            Language::French => include_str!("../../assets/lang/fr.lang"),
+            Language::Spanish => include_str!("../../assets/lang/es.lang"),
+        }
+    }
+
+    /// Returns whether a count takes the singular form of a message.
+    fn is_singular(self, count: u32) -> bool {
+        match self {
+            Language::English | Language::Spanish => count == 1,
+            // French uses the singular for zero as well.
+            Language::French => count <= 1,
+        }
+    }
+}
+
+impl FromStr for Language {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "en" => Ok(Language::English),
+            "fr" => Ok(Language::French),
+            "es" => Ok(Language::Spanish),
+            _ => Err(format!("unknown language `{s}`, expected en, fr or es")),
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let code = match self {
+            Language::English => "en",
+            Language::French => "fr",
+            Language::Spanish => "es",
+        };
+        write!(f, "{code}")
+    }
+}
+
+/// The UI text of one language, looked up by message id.
+/// Messages missing from a translation are shown in English.
+pub struct Strings {
+    language: Language,
+    messages: HashMap<String, String>,
+}
+
+impl Strings {
+    pub fn new(language: Language) -> Self {
+        let mut messages = parse(Language::English.table());
+        messages.extend(parse(language.table()));
+
+        Self { language, messages }
+    }
+
+    /// Returns the message with each `{name}` replaced by its value.
+    /// Unknown ids are returned as they are, so they stand out on screen.
+    pub fn get(&self, id: &str, args: &[(&str, &str)]) -> String {
+        let mut message = self
+            .messages
+            .get(id)
+            .cloned()
+            .unwrap_or_else(|| id.to_string());
+        for (name, value) in args {
+            message = message.replace(&format!("{{{name}}}"), value);
+        }
+        message
+    }
+
+    /// Returns the singular (`id.one`) or plural (`id.other`) form of the message for the
+    /// count, with `{count}` replaced as well as the other arguments.
+    pub fn plural(&self, id: &str, count: u32, args: &[(&str, &str)]) -> String {
+        let form = if self.language.is_singular(count) {
+            "one"
+        } else {
+            "other"
+        };
+        let count = count.to_string();
+        let args = [&[("count", count.as_str())], args].concat();
+
+        self.get(&format!("{id}.{form}"), &args)
+    }
+}
+
+/// Parses `id = message` lines, skipping blank lines and `#` comments.
+fn parse(table: &str) -> HashMap<String, String> {
+    table
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(id, message)| (id.trim().to_string(), message.trim().to_string()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Language, Strings, parse};
+    use crate::game::draw_utils::is_drawable;
+
+    const LANGUAGES: [Language; 3] = [Language::English, Language::French, Language::Spanish];
+
+    #[test]
+    fn every_language_translates_every_message() {
+        let english = parse(Language::English.table());
+
+        for language in LANGUAGES {
+            let table = parse(language.table());
+            for id in english.keys() {
+                assert!(table.contains_key(id), "{language} is missing {id}");
+            }
+            for message in table.values() {
+                assert!(message.chars().all(is_drawable), "{language}: {message}");
+            }
+        }
+    }
+
+    #[test]
+    fn fills_in_placeholders_and_plurals() {
+        let english = Strings::new(Language::English);
+        let french = Strings::new(Language::French);
+
+        assert_eq!(english.get("hud.best", &[("score", "7")]), "best 7");
+        assert_eq!(english.plural("dead.score", 1, &[]), "you got 1 point!");
+        assert_eq!(english.plural("dead.score", 0, &[]), "you got 0 points!");
+        assert_eq!(
+            french.plural("dead.score", 0, &[]),
+            "tu as marqué 0 point !"
+        );
+        assert_eq!(english.get("no.such.message", &[]), "no.such.message");
+    }
+}
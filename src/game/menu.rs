@@ -0,0 +1,180 @@
+use crate::config::{MENU_FONT_PIXEL_SIZE, MENU_ITEM_HEIGHT, WIDTH};
+use crate::game::text::{Align, TextStyle, draw_text, measure_text};
+use crate::game::theme::Palette;
+
+/// What picking a menu item does.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MenuAction {
+    Play,
+    /// A run on the seed everyone gets today.
+    DailyChallenge,
+    HighScores,
+    /// Opens the list of levels.
+    Levels,
+    /// Plays the level at the index.
+    Level(usize),
+    Settings,
+    Quit,
+    Retry,
+    MainMenu,
+    SaveReplay,
+    Back,
+    Volume,
+    FlapKey,
+    ConsoleKey,
+    WindowScale,
+    Theme,
+    Difficulty,
+    Progressive,
+    ShowFps,
+}
+
+/// A column of items centred on the screen, one of them selected.
+/// Items are chosen with the arrow keys or by pointing at them.
+pub struct Menu {
+    /// Each item's label, and what it does.
+    items: Vec<(String, MenuAction)>,
+    selected: usize,
+    /// Top of the first item.
+    top: i32,
+}
+
+impl Menu {
+    /// Every menu has at least one item, so there is always one to select.
+    pub fn new(top: i32, items: Vec<(String, MenuAction)>) -> Self {
+        debug_assert!(!items.is_empty(), "menu without items");
+        Self {
+            items,
+            selected: 0,
+            top,
+        }
+    }
+
+    /// Selects the item at the index, used to keep the selection when a menu is rebuilt.
+    pub fn with_selected(mut self, index: usize) -> Self {
+        self.selected = index.min(self.items.len() - 1);
+        self
+    }
+
+    pub fn selected_index(&self) -> usize {
+        self.selected
+    }
+
+    /// Selects the item above, wrapping around to the last one.
+    pub fn previous(&mut self) -> () {
+        self.selected = (self.selected + self.items.len() - 1) % self.items.len();
+    }
+
+    /// Selects the item below, wrapping around to the first one.
+    pub fn next(&mut self) -> () {
+        self.selected = (self.selected + 1) % self.items.len();
+    }
+
+    pub fn selected(&self) -> MenuAction {
+        self.items[self.selected].1
+    }
+
+    /// Returns the index of the item whose label, with its selection marks, is under the
+    /// given point.
+    fn item_at(&self, x: f32, y: f32) -> Option<usize> {
+        let row = (y - self.top as f32) / MENU_ITEM_HEIGHT as f32;
+        if row < 0.0 || row as usize >= self.items.len() {
+            return None;
+        }
+
+        let (label, _) = &self.items[row as usize];
+        let (width, _) = measure_text(&format!("> {label} <"), &self.style());
+        let offset = (x - WIDTH as f32 / 2.0).abs();
+        (offset <= width as f32 / 2.0).then_some(row as usize)
+    }
+
+    /// Selects the item under the pointer, if there is one.
+    pub fn hover(&mut self, x: f32, y: f32) -> () {
+        if let Some(i) = self.item_at(x, y) {
+            self.selected = i;
+        }
+    }
+
+    /// Selects and returns the action of the item that was clicked, if there is one.
+    pub fn click(&mut self, x: f32, y: f32) -> Option<MenuAction> {
+        let i = self.item_at(x, y)?;
+        self.selected = i;
+        Some(self.items[i].1)
+    }
+
+    fn style(&self) -> TextStyle {
+        TextStyle::new()
+            .with_scale(MENU_FONT_PIXEL_SIZE)
+            .with_align(Align::Centre)
+    }
+
+    /// Draws the items with the selected one highlighted and marked.
+    pub fn draw(&self, frame: &mut [u8], palette: &Palette) -> () {
+        let style = self.style().with_palette(palette);
+        // Centre each label in its row.
+        let padding = (MENU_ITEM_HEIGHT as i32 - style.line_height() as i32) / 2;
+
+        for (i, (label, _)) in self.items.iter().enumerate() {
+            let (text, style) = if i == self.selected {
+                (format!("> {label} <"), style)
+            } else {
+                (label.clone(), style.with_color(palette.muted))
+            };
+            let top = self.top + (i as u32 * MENU_ITEM_HEIGHT) as i32 + padding;
+
+            draw_text(frame, &text, WIDTH as i32 / 2, top, &style);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Menu, MenuAction};
+    use crate::config::{MENU_ITEM_HEIGHT, WIDTH};
+
+    fn menu() -> Menu {
+        Menu::new(
+            100,
+            vec![
+                ("play".to_string(), MenuAction::Play),
+                ("settings".to_string(), MenuAction::Settings),
+                ("quit".to_string(), MenuAction::Quit),
+            ],
+        )
+    }
+
+    #[test]
+    fn selection_wraps_around() {
+        let mut menu = menu();
+
+        menu.previous();
+        assert_eq!(menu.selected(), MenuAction::Quit);
+        menu.next();
+        menu.next();
+        assert_eq!(menu.selected(), MenuAction::Settings);
+    }
+
+    #[test]
+    fn pointer_picks_the_label_under_it() {
+        let mut menu = menu();
+        let centre = WIDTH as f32 / 2.0;
+        let second_row = 100.0 + MENU_ITEM_HEIGHT as f32 * 1.5;
+
+        menu.hover(centre, second_row);
+        assert_eq!(menu.selected(), MenuAction::Settings);
+        menu.hover(centre, 99.0);
+        assert_eq!(menu.selected(), MenuAction::Settings);
+
+        assert_eq!(menu.click(centre, 100.0), Some(MenuAction::Play));
+        assert_eq!(menu.selected(), MenuAction::Play);
+        assert_eq!(
+            menu.click(centre, 100.0 + 3.0 * MENU_ITEM_HEIGHT as f32),
+            None
+        );
+
+        // Beside a label on its row is not on it.
+        assert_eq!(menu.click(10.0, second_row), None);
+        assert_eq!(menu.click(WIDTH as f32 - 10.0, second_row), None);
+        assert_eq!(menu.selected(), MenuAction::Play);
+    }
+}
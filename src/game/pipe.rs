@@ -1,60 +1,154 @@
 use crate::config::{
-    HEIGHT, PIPE_A_COLOR, PIPE_B_COLOR, PIPE_G_COLOR, PIPE_GAP_BOUND, PIPE_GAP_SIZE, PIPE_R_COLOR,
-    PIPE_SPEED, PIPE_WIDTH, WIDTH,
+    GROUND_Y, PIPE_A_COLOR, PIPE_B_COLOR, PIPE_G_COLOR, PIPE_GAP_BOUND, PIPE_R_COLOR,
+    PIPE_SPAWN_TABLE, PIPE_WIDTH, WIDTH,
 };
-use crate::game::collision_box::CollisionBox;
+use crate::game::collision_box::{CollisionBox, Hitbox};
+use crate::game::difficulty::PipeTuning;
+use crate::game::pipe_behaviour::{Gap, PipeBehaviour};
 use crate::game::vector2::Vector2;
 use rand::Rng;
-use rand::rngs::ThreadRng;
+use rand_pcg::Pcg32;
+use std::ops::Range;
 
 /// Struct for the pipe.
 /// A pipe's position represents the top left corner of them.
 pub struct Pipe {
     pub position: Vector2,
+    /// Position before the last update, used to interpolate rendering between updates.
+    previous_position: Vector2,
     velocity: Vector2,
-    y_gap_location: u32,
     pub passed: bool,
 
-    pub upper_collision_box: CollisionBox,
-    pub lower_collision_box: CollisionBox,
+    behaviour: PipeBehaviour,
+    /// Where the gap was placed, the behaviour moves it around this.
+    placed_gap: Gap,
+    gap: Gap,
+    /// Gap before the last update, used to interpolate rendering between updates.
+    previous_gap: Gap,
+    /// Seconds since the pipe was created.
+    age: f32,
 }
 
 impl Pipe {
-    /// On creation, pipes are placed just past the right side of the screen.
-    pub fn new(rng: &mut ThreadRng) -> Self {
-        let y_gap_location =
-            rng.random_range(PIPE_GAP_BOUND..HEIGHT - PIPE_GAP_BOUND - PIPE_GAP_SIZE);
+    /// On creation, pipes are placed just past the right side of the screen, behaving as
+    /// picked from the spawn table.
+    /// The top of the gap is picked from `reachable` where it overlaps the playfield, so
+    /// the bird can make it from the previous gap.
+    pub fn new(rng: &mut Pcg32, tuning: PipeTuning, reachable: Range<f32>) -> Self {
+        let behaviour = PipeBehaviour::pick(rng, &PIPE_SPAWN_TABLE);
+        Self::with_behaviour(rng, tuning, behaviour, reachable)
+    }
+
+    /// The lowest top a gap with the tuning's size and the behaviour can be placed at and
+    /// one past the highest, leaving room for it to move within the playfield.
+    pub fn tops(tuning: PipeTuning, behaviour: PipeBehaviour) -> (u32, u32) {
+        let bound = PIPE_GAP_BOUND + behaviour.reach().ceil() as u32;
+        (bound, GROUND_Y - bound - tuning.gap_size)
+    }
 
-        let min_upper = Vector2::new(WIDTH as f32, 0.0);
-        let max_upper = Vector2::new((WIDTH + PIPE_WIDTH) as f32, y_gap_location as f32);
+    /// Creates a pipe with the given behaviour, leaving room for its gap to move.
+    pub fn with_behaviour(
+        rng: &mut Pcg32,
+        tuning: PipeTuning,
+        behaviour: PipeBehaviour,
+        reachable: Range<f32>,
+    ) -> Self {
+        let gap_size = tuning.gap_size;
+        let (lowest, highest) = Self::tops(tuning, behaviour);
+        debug_assert!(
+            reachable.start.ceil().max(lowest as f32)
+                <= reachable.end.floor().min((highest - 1) as f32),
+            "no top within reach"
+        );
+        // Release builds fall back to the closest top in the playfield.
+        let start = (reachable.start.ceil() as u32).clamp(lowest, highest - 1);
+        let end = (reachable.end.floor() as u32)
+            .saturating_add(1)
+            .clamp(start + 1, highest);
+        let y_gap_location = rng.random_range(start..end);
+        let gap = Gap {
+            top: y_gap_location as f32,
+            size: gap_size as f32,
+        };
 
-        let min_lower = Vector2::new(WIDTH as f32, (y_gap_location + PIPE_GAP_SIZE) as f32);
-        let max_lower = Vector2::new((WIDTH + PIPE_WIDTH) as f32, HEIGHT as f32);
+        Self::placed(WIDTH as f32, tuning.speed, gap, behaviour)
+    }
+
+    /// Creates a pipe at `x` with its gap placed as given, such as a level's pipe.
+    pub fn placed(x: f32, speed: f32, gap: Gap, behaviour: PipeBehaviour) -> Self {
+        let position = Vector2::new(x, 0.0);
 
         Self {
-            position: min_upper,
-            velocity: Vector2::left() * PIPE_SPEED,
-            y_gap_location,
+            position,
+            previous_position: position,
+            velocity: Vector2::left() * speed,
             passed: false,
-            upper_collision_box: CollisionBox::new(min_upper, max_upper),
-            lower_collision_box: CollisionBox::new(min_lower, max_lower),
+            behaviour,
+            placed_gap: gap,
+            gap: behaviour.gap(gap, 0.0),
+            previous_gap: behaviour.gap(gap, 0.0),
+            age: 0.0,
         }
     }
 
+    /// Changes how fast the pipe moves left, in pixels per second.
+    pub fn set_speed(&mut self, speed: f32) -> () {
+        self.velocity = Vector2::left() * speed;
+    }
+
     pub fn update(&mut self, dt: f32) -> () {
+        self.previous_position = self.position;
         self.position = self.position + self.velocity * dt;
 
-        // Update collision boxes
-        self.upper_collision_box.min = self.upper_collision_box.min + self.velocity * dt;
-        self.upper_collision_box.max = self.upper_collision_box.max + self.velocity * dt;
+        self.age += dt;
+        self.previous_gap = self.gap;
+        self.gap = self.behaviour.gap(self.placed_gap, self.age);
+    }
+
+    /// Seconds since the pipe was created.
+    pub fn age(&self) -> f32 {
+        self.age
+    }
+
+    pub fn behaviour(&self) -> PipeBehaviour {
+        self.behaviour
+    }
+
+    /// Where the gap was placed, before its behaviour moves it.
+    pub fn placed_gap(&self) -> Gap {
+        self.placed_gap
+    }
+
+    /// The upper and lower boxes, derived from the position and gap.
+    pub fn collision_boxes(&self) -> [CollisionBox; 2] {
+        Self::collision_boxes_at(self.position, self.gap)
+    }
+
+    /// The collision boxes where the pipe and its gap were before the last update.
+    pub fn previous_collision_boxes(&self) -> [CollisionBox; 2] {
+        Self::collision_boxes_at(self.previous_position, self.previous_gap)
+    }
 
-        self.lower_collision_box.min = self.lower_collision_box.min + self.velocity * dt;
-        self.lower_collision_box.max = self.lower_collision_box.max + self.velocity * dt;
+    /// The halves above and below the gap, relative to the position.
+    fn collision_boxes_at(position: Vector2, gap: Gap) -> [CollisionBox; 2] {
+        let upper = Hitbox::new(
+            Vector2::new(0.0, 0.0),
+            Vector2::new(PIPE_WIDTH as f32, gap.top),
+        );
+        let lower = Hitbox::new(
+            Vector2::new(0.0, gap.bottom()),
+            Vector2::new(PIPE_WIDTH as f32, GROUND_Y as f32 - gap.bottom()),
+        );
+        [upper.at(position), lower.at(position)]
     }
 
-    pub fn draw(&self, frame: &mut [u8]) -> () {
+    /// Draws the pipe `alpha` of the way from its previous position to its current one.
+    // `idx + 0` lines the channels up with the ones after it.
+    #[allow(clippy::identity_op)]
+    pub fn draw(&self, frame: &mut [u8], alpha: f32) -> () {
         let stride = WIDTH as usize * 4;
-        let x_position = self.position.x as i32;
+        let x_position = self.previous_position.lerp(self.position, alpha).x as i32;
+        let gap = self.previous_gap.lerp(self.gap, alpha);
 
         for x in x_position..x_position + PIPE_WIDTH as i32 {
             // Don't draw out of bounds
@@ -62,10 +156,8 @@ impl Pipe {
                 continue;
             }
 
-            for y in 0..HEIGHT as usize {
-                if y > self.y_gap_location as usize
-                    && y < (self.y_gap_location + PIPE_GAP_SIZE) as usize
-                {
+            for y in 0..GROUND_Y as usize {
+                if y as f32 > gap.top && (y as f32) < gap.bottom() {
                     continue;
                 }
 
@@ -79,3 +171,38 @@ impl Pipe {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Pipe;
+    use crate::config::{GROUND_Y, PIPE_SPAWN_TABLE};
+    use crate::game::Difficulty;
+    use rand::SeedableRng;
+    use rand_pcg::Pcg32;
+
+    #[test]
+    fn collision_boxes_follow_a_moving_gap() {
+        let mut rng = Pcg32::seed_from_u64(9);
+
+        for (_, behaviour) in PIPE_SPAWN_TABLE {
+            let mut pipe = Pipe::with_behaviour(
+                &mut rng,
+                Difficulty::Hard.pipes(),
+                behaviour,
+                f32::NEG_INFINITY..f32::INFINITY,
+            );
+
+            for _ in 0..600 {
+                pipe.update(1.0 / 120.0);
+
+                let [upper, lower] = pipe.collision_boxes();
+                assert_eq!(upper.max.y, pipe.gap.top);
+                assert_eq!(lower.min.y, pipe.gap.bottom());
+                assert_eq!(lower.max.y, GROUND_Y as f32);
+
+                let [previous_upper, _] = pipe.previous_collision_boxes();
+                assert_eq!(previous_upper.max.y, pipe.previous_gap.top);
+            }
+        }
+    }
+}
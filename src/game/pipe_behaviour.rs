@@ -0,0 +1,171 @@
+use crate::config::{BREATHING_PIPE, OSCILLATING_PIPE, SLIDING_PIPE};
+use rand::Rng;
+use rand_pcg::Pcg32;
+use std::f32::consts::TAU;
+use std::str::FromStr;
+
+/// Where a pipe's gap is at a moment, in pixels from the top of the playfield.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Gap {
+    pub top: f32,
+    pub size: f32,
+}
+
+impl Gap {
+    pub fn bottom(&self) -> f32 {
+        self.top + self.size
+    }
+
+    /// Returns the gap `t` of the way from self (t = 0) to `other` (t = 1).
+    pub fn lerp(&self, other: Gap, t: f32) -> Gap {
+        Gap {
+            top: self.top + (other.top - self.top) * t,
+            size: self.size + (other.size - self.size) * t,
+        }
+    }
+}
+
+/// How a pipe's gap moves while the pipe is alive, picked from the spawn table when the
+/// pipe is created.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PipeBehaviour {
+    /// The gap stays where it was placed, the classic pipe.
+    Static,
+    /// The gap moves up and down `amplitude` pixels around where it was placed, once
+    /// every `period` seconds.
+    Oscillating { amplitude: f32, period: f32 },
+    /// The gap opens and closes by up to `amount` pixels around its middle, once every
+    /// `period` seconds.
+    Breathing { amount: f32, period: f32 },
+    /// The upper half drops down from the top of the screen, reaching its place after
+    /// `seconds`.
+    SlideIn { seconds: f32 },
+}
+
+impl PipeBehaviour {
+    /// Picks a behaviour from a table of weighted entries, each entry being picked in
+    /// proportion to its weight.
+    pub fn pick(rng: &mut Pcg32, table: &[(u32, PipeBehaviour)]) -> Self {
+        let total: u32 = table.iter().map(|&(weight, _)| weight).sum();
+        let mut roll = rng.random_range(0..total);
+
+        for &(weight, behaviour) in table {
+            if roll < weight {
+                return behaviour;
+            }
+            roll -= weight;
+        }
+        unreachable!("the roll is below the total weight")
+    }
+
+    /// How far past where it was placed the gap can reach, above and below.
+    /// Pipes are placed with this much extra room so the gap stays in the playfield.
+    pub fn reach(self) -> f32 {
+        match self {
+            PipeBehaviour::Static | PipeBehaviour::SlideIn { .. } => 0.0,
+            PipeBehaviour::Oscillating { amplitude, .. } => amplitude,
+            PipeBehaviour::Breathing { amount, .. } => amount / 2.0,
+        }
+    }
+
+    /// Returns the gap `age` seconds after the pipe was created, given where it was placed.
+    pub fn gap(self, placed: Gap, age: f32) -> Gap {
+        match self {
+            PipeBehaviour::Static => placed,
+            PipeBehaviour::Oscillating { amplitude, period } => Gap {
+                top: placed.top + amplitude * (TAU * age / period).sin(),
+                size: placed.size,
+            },
+            PipeBehaviour::Breathing { amount, period } => {
+                let change = amount * (TAU * age / period).sin();
+                Gap {
+                    top: placed.top - change / 2.0,
+                    size: placed.size + change,
+                }
+            }
+            PipeBehaviour::SlideIn { seconds } => {
+                // Eased out, so the half slows down as it settles into place.
+                let t = (age / seconds).min(1.0);
+                let top = placed.top * (1.0 - (1.0 - t).powi(2));
+                Gap {
+                    top,
+                    size: placed.bottom() - top,
+                }
+            }
+        }
+    }
+}
+
+impl FromStr for PipeBehaviour {
+    type Err = String;
+
+    /// Level files name the behaviours, the moving ones use the settings from the config.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "static" => Ok(PipeBehaviour::Static),
+            "oscillating" => Ok(OSCILLATING_PIPE),
+            "breathing" => Ok(BREATHING_PIPE),
+            "sliding" => Ok(SLIDING_PIPE),
+            _ => Err(format!(
+                "unknown pipe behaviour `{s}`, expected static, oscillating, breathing or sliding"
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Gap, PipeBehaviour};
+    use crate::config::{GROUND_Y, PIPE_GAP_BOUND, PIPE_SPAWN_TABLE};
+    use rand::SeedableRng;
+    use rand_pcg::Pcg32;
+
+    #[test]
+    fn gaps_placed_with_their_reach_stay_in_the_playfield() {
+        for (_, behaviour) in PIPE_SPAWN_TABLE {
+            let bound = PIPE_GAP_BOUND as f32 + behaviour.reach();
+            let size = 100.0;
+            // The highest and lowest places a pipe can put its gap.
+            for top in [bound, GROUND_Y as f32 - bound - size] {
+                let placed = Gap { top, size };
+                for step in 0..1000 {
+                    let gap = behaviour.gap(placed, step as f32 * 0.01);
+                    assert!(gap.top >= 0.0);
+                    assert!(gap.bottom() <= (GROUND_Y - PIPE_GAP_BOUND) as f32 + 1e-3);
+                    assert!(gap.size > 0.0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn spawn_table_picks_entries_by_weight() {
+        let mut rng = Pcg32::seed_from_u64(0);
+        let table = [
+            (3, PipeBehaviour::Static),
+            (1, PipeBehaviour::SlideIn { seconds: 1.0 }),
+            (
+                0,
+                PipeBehaviour::Oscillating {
+                    amplitude: 1.0,
+                    period: 1.0,
+                },
+            ),
+        ];
+
+        let picks: Vec<PipeBehaviour> = (0..4000)
+            .map(|_| PipeBehaviour::pick(&mut rng, &table))
+            .collect();
+        let statics = picks
+            .iter()
+            .filter(|&&b| b == PipeBehaviour::Static)
+            .count();
+
+        assert!((2800..3200).contains(&statics));
+        assert!(
+            picks
+                .iter()
+                .all(|b| !matches!(b, PipeBehaviour::Oscillating { .. }))
+        );
+    }
+}
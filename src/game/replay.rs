@@ -0,0 +1,214 @@
+use crate::config::{
+    DEFAULT_CEILING_BEHAVIOUR, DEFAULT_COLLIDER_SHAPE, DEFAULT_DIFFICULTY, DEFAULT_PHYSICS_PRESET,
+    DEFAULT_TICK_RATE, REPLAY_VERSION,
+};
+use crate::game::{CeilingBehaviour, ColliderShape, Difficulty, PhysicsPreset, TickRate};
+use std::fs::{self, OpenOptions};
+use std::io::{Error, ErrorKind::InvalidData, Write};
+use std::path::Path;
+
+/// A recorded run.
+/// Runs are deterministic given the seed, so the seed plus the ticks at which
+/// the bird flapped is enough to reproduce every frame.
+///
+/// Stored on disk as plain text, one entry per line:
+/// ```text
+/// version 2
+/// seed 1234
+/// tick_rate 120
+/// physics classic
+/// ceiling kill
+/// collider circle
+/// difficulty normal
+/// progressive true
+/// flap 30
+/// flap 95
+/// ```
+/// The version is that of the pipe generation, replays from other versions would fly a
+/// different course so they aren't loaded.
+#[derive(Debug, PartialEq)]
+pub struct Replay {
+    pub seed: u64,
+    /// Rate the run was simulated at, flaps are in ticks of this rate.
+    pub tick_rate: TickRate,
+    pub physics_preset: PhysicsPreset,
+    pub ceiling_behaviour: CeilingBehaviour,
+    pub collider_shape: ColliderShape,
+    pub difficulty: Difficulty,
+    pub progressive: bool,
+    /// Ticks at which the bird flapped, in ascending order.
+    pub flaps: Vec<u64>,
+}
+
+impl Replay {
+    /// Returns how many flaps happened right before the given tick was simulated.
+    pub fn flaps_at(&self, tick: u64) -> usize {
+        let start = self.flaps.partition_point(|&t| t < tick);
+        let end = self.flaps.partition_point(|&t| t <= tick);
+        end - start
+    }
+
+    /// Saves the replay to a new file, failing with `AlreadyExists` rather than
+    /// overwriting one.
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut contents = format!(
+            "version {REPLAY_VERSION}\nseed {}\ntick_rate {}\nphysics {}\nceiling {}\ncollider {}\ndifficulty {}\nprogressive {}\n",
+            self.seed,
+            self.tick_rate,
+            self.physics_preset,
+            self.ceiling_behaviour,
+            self.collider_shape,
+            self.difficulty,
+            self.progressive
+        );
+        for tick in &self.flaps {
+            contents += &format!("flap {tick}\n");
+        }
+
+        OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(path)?
+            .write_all(contents.as_bytes())
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let contents = fs::read_to_string(path)?;
+
+        // Replays saved before the version was written are the first version.
+        let mut version = 1;
+        let mut seed = None;
+        // Replays recorded before these were configurable used the defaults.
+        let mut tick_rate = DEFAULT_TICK_RATE;
+        let mut physics_preset = DEFAULT_PHYSICS_PRESET;
+        let mut ceiling_behaviour = DEFAULT_CEILING_BEHAVIOUR;
+        let mut collider_shape = DEFAULT_COLLIDER_SHAPE;
+        let mut difficulty = DEFAULT_DIFFICULTY;
+        let mut progressive = false;
+        let mut flaps = Vec::new();
+
+        for (line_number, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let invalid = || Error::new(InvalidData, format!("line {}: {line}", line_number + 1));
+            let (key, value) = line.split_once(' ').ok_or_else(invalid)?;
+
+            match key {
+                "version" => version = value.trim().parse().map_err(|_| invalid())?,
+                "seed" => seed = Some(value.trim().parse().map_err(|_| invalid())?),
+                "tick_rate" => tick_rate = value.parse().map_err(|_| invalid())?,
+                "physics" => physics_preset = value.parse().map_err(|_| invalid())?,
+                "ceiling" => ceiling_behaviour = value.parse().map_err(|_| invalid())?,
+                "collider" => collider_shape = value.parse().map_err(|_| invalid())?,
+                "difficulty" => difficulty = value.parse().map_err(|_| invalid())?,
+                "progressive" => progressive = value.trim().parse().map_err(|_| invalid())?,
+                "flap" => flaps.push(value.trim().parse().map_err(|_| invalid())?),
+                _ => return Err(invalid()),
+            }
+        }
+
+        if version != REPLAY_VERSION {
+            return Err(Error::new(
+                InvalidData,
+                format!(
+                    "replay is from version {version} of the pipe generation, this build plays \
+                     version {REPLAY_VERSION}"
+                ),
+            ));
+        }
+        flaps.sort_unstable();
+
+        Ok(Self {
+            seed: seed.ok_or_else(|| Error::new(InvalidData, "replay has no seed"))?,
+            tick_rate,
+            physics_preset,
+            ceiling_behaviour,
+            collider_shape,
+            difficulty,
+            progressive,
+            flaps,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Replay;
+    use crate::game::{CeilingBehaviour, ColliderShape, Difficulty, Game, PhysicsPreset, TickRate};
+    use std::io::ErrorKind;
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let replay = Replay {
+            seed: 42,
+            tick_rate: TickRate::Hz240,
+            physics_preset: PhysicsPreset::Heavy,
+            ceiling_behaviour: CeilingBehaviour::Bounce,
+            collider_shape: ColliderShape::Mask,
+            difficulty: Difficulty::Hard,
+            progressive: true,
+            flaps: vec![3, 3, 40, 200],
+        };
+        let path = std::env::temp_dir().join(format!(
+            "rust-game-replay-round-trip-{}.replay",
+            std::process::id()
+        ));
+
+        replay.save(&path).unwrap();
+        assert_eq!(Replay::load(&path).unwrap(), replay);
+        let error = replay.save(&path).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::AlreadyExists);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(replay.flaps_at(3), 2);
+        assert_eq!(replay.flaps_at(4), 0);
+    }
+
+    #[test]
+    fn replays_from_other_versions_are_rejected() {
+        let path = std::env::temp_dir().join(format!(
+            "rust-game-replay-old-version-{}.replay",
+            std::process::id()
+        ));
+
+        for contents in ["seed 42\nflap 3\n", "version 1\nseed 42\nflap 3\n"] {
+            std::fs::write(&path, contents).unwrap();
+            let error = Replay::load(&path).unwrap_err();
+            assert!(error.to_string().contains("version 1"), "{error}");
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn playback_reproduces_the_recorded_run() {
+        let mut game = Game::with_seed(7)
+            .with_tick_rate(TickRate::Hz60)
+            .with_physics_preset(PhysicsPreset::Floaty);
+        let dt = game.tick_rate().dt();
+        game.start_run();
+        while game.is_playing() {
+            if game.tick().is_multiple_of(50) {
+                game.space_bar_hit();
+            }
+            game.update(dt);
+        }
+        let replay = game.replay();
+
+        let mut playback = Game::from_replay(&replay);
+        while playback.is_playing() {
+            for _ in 0..replay.flaps_at(playback.tick()) {
+                playback.space_bar_hit();
+            }
+            playback.update(playback.tick_rate().dt());
+        }
+
+        assert_eq!(playback.tick(), game.tick());
+        assert_eq!(playback.replay(), replay);
+    }
+}
@@ -1,19 +1,150 @@
-use crate::game::draw_utils::draw_number;
+use crate::config::{
+    FONT_PIXEL_SIZE_SCREEN_PIXELS, FONT_START_OFFSET, SCORE_GHOST_GAP, SCORE_POP_EXTRA_PIXEL_SIZE,
+    SCORE_POP_SECONDS, SCORE_STAGE_GAP, UI_HINT_FONT_PIXEL_SIZE, WIDTH,
+};
+use crate::game::text::{Align, TextStyle, draw_text, measure_text};
+use crate::game::theme::Palette;
+use std::fmt;
+use std::str::FromStr;
+
+/// Where the score is drawn while playing, picked in the settings.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScorePosition {
+    TopLeft,
+    /// Centred at the top, like the original game.
+    TopCentre,
+}
+
+impl FromStr for ScorePosition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "top_left" => Ok(ScorePosition::TopLeft),
+            "top_centre" => Ok(ScorePosition::TopCentre),
+            _ => Err(format!(
+                "unknown score position `{s}`, expected top_left or top_centre"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ScorePosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ScorePosition::TopLeft => "top_left",
+            ScorePosition::TopCentre => "top_centre",
+        };
+        write!(f, "{name}")
+    }
+}
 
 pub struct Score {
     pub score: u32,
+    /// Seconds left of the pop played when the score goes up.
+    pop: f32,
 }
 
 impl Score {
     pub fn new() -> Self {
-        Self { score: 0 }
+        Self { score: 0, pop: 0.0 }
     }
 
     pub fn increase_score(&mut self) -> () {
-        self.score += 1;
+        self.add(1);
+    }
+
+    /// Adds points with the same pop as passing a pipe, used for coins.
+    pub fn add(&mut self, points: u32) -> () {
+        self.score += points;
+        self.pop = SCORE_POP_SECONDS;
     }
 
-    pub fn draw(&self, frame: &mut [u8]) -> () {
-        draw_number(frame, self.score);
+    pub fn update(&mut self, dt: f32) -> () {
+        self.pop = (self.pop - dt).max(0.0);
+    }
+
+    /// Draws the score, with the label for the best score so far next to it and the label
+    /// for the difficulty stage under it if there are any.
+    pub fn draw(
+        &self,
+        frame: &mut [u8],
+        position: ScorePosition,
+        best: Option<&str>,
+        stage: Option<&str>,
+        palette: &Palette,
+    ) -> () {
+        let text = self.score.to_string();
+        let style = TextStyle::new().with_palette(palette);
+        let (width, height) = measure_text(&text, &style);
+        let (left, top) = match position {
+            ScorePosition::TopLeft => (FONT_START_OFFSET, FONT_START_OFFSET),
+            ScorePosition::TopCentre => ((WIDTH - width) / 2, FONT_START_OFFSET),
+        };
+        // Middle of the score at its resting size, the pop grows around it.
+        let center_x = left as i32 + width as i32 / 2;
+        let center_y = top as i32 + height as i32 / 2;
+
+        let extra = (SCORE_POP_EXTRA_PIXEL_SIZE as f32 * self.pop / SCORE_POP_SECONDS).round();
+        let popped = style
+            .with_scale(FONT_PIXEL_SIZE_SCREEN_PIXELS + extra as u32)
+            .with_align(Align::Centre);
+        let (_, popped_height) = measure_text(&text, &popped);
+        draw_text(
+            frame,
+            &text,
+            center_x,
+            center_y - popped_height as i32 / 2,
+            &popped,
+        );
+
+        let label_style = TextStyle::new()
+            .with_scale(UI_HINT_FONT_PIXEL_SIZE)
+            .with_palette(palette)
+            .with_color(palette.muted);
+
+        if let Some(ghost) = best {
+            let (_, ghost_height) = measure_text(ghost, &label_style);
+            draw_text(
+                frame,
+                ghost,
+                (left + width + SCORE_GHOST_GAP) as i32,
+                center_y - ghost_height as i32 / 2,
+                &label_style,
+            );
+        }
+
+        if let Some(stage) = stage {
+            // Lined up with the score, below where the pop can reach.
+            let (x, align) = match position {
+                ScorePosition::TopLeft => (left as i32, Align::Left),
+                ScorePosition::TopCentre => (center_x, Align::Centre),
+            };
+            draw_text(
+                frame,
+                stage,
+                x,
+                (top + height + SCORE_STAGE_GAP) as i32,
+                &label_style.with_align(align),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Score;
+    use crate::config::SCORE_POP_SECONDS;
+
+    #[test]
+    fn pop_fades_after_an_increase() {
+        let mut score = Score::new();
+        score.increase_score();
+        assert_eq!(score.pop, SCORE_POP_SECONDS);
+
+        score.update(SCORE_POP_SECONDS / 2.0);
+        assert!(score.pop > 0.0);
+        score.update(SCORE_POP_SECONDS);
+        assert_eq!(score.pop, 0.0);
     }
 }
@@ -0,0 +1,74 @@
+use crate::config::{
+    BIRD_A_COLOR, BIRD_B_COLOR, BIRD_BEAK_B_COLOR, BIRD_BEAK_G_COLOR, BIRD_BEAK_R_COLOR,
+    BIRD_EYE_B_COLOR, BIRD_EYE_G_COLOR, BIRD_EYE_R_COLOR, BIRD_G_COLOR, BIRD_HEIGHT, BIRD_R_COLOR,
+    BIRD_WIDTH,
+};
+use crate::game::draw_utils::draw_pixel;
+
+/// A small RGBA image, pixels with zero alpha are not drawn.
+pub struct Sprite {
+    pub width: u32,
+    pub height: u32,
+    pixels: Vec<[u8; 4]>,
+}
+
+impl Sprite {
+    /// The round bird: a disc filling the bird's box with an eye and a beak.
+    pub fn bird() -> Self {
+        let radius = BIRD_WIDTH.min(BIRD_HEIGHT) as f32 / 2.0;
+        let inside = |x: u32, y: u32, center_x: f32, center_y: f32, radius: f32| {
+            let dx = x as f32 + 0.5 - center_x;
+            let dy = y as f32 + 0.5 - center_y;
+            dx * dx + dy * dy <= radius * radius
+        };
+
+        let mut pixels = Vec::with_capacity((BIRD_WIDTH * BIRD_HEIGHT) as usize);
+        for y in 0..BIRD_HEIGHT {
+            for x in 0..BIRD_WIDTH {
+                let pixel = if !inside(x, y, radius, radius, radius) {
+                    [0, 0, 0, 0]
+                } else if inside(x, y, radius * 1.4, radius * 0.6, radius * 0.15) {
+                    [
+                        BIRD_EYE_R_COLOR,
+                        BIRD_EYE_G_COLOR,
+                        BIRD_EYE_B_COLOR,
+                        BIRD_A_COLOR,
+                    ]
+                } else if x as f32 >= radius * 1.5 && (y as f32 - radius).abs() < radius * 0.2 {
+                    [
+                        BIRD_BEAK_R_COLOR,
+                        BIRD_BEAK_G_COLOR,
+                        BIRD_BEAK_B_COLOR,
+                        BIRD_A_COLOR,
+                    ]
+                } else {
+                    [BIRD_R_COLOR, BIRD_G_COLOR, BIRD_B_COLOR, BIRD_A_COLOR]
+                };
+                pixels.push(pixel);
+            }
+        }
+
+        Self {
+            width: BIRD_WIDTH,
+            height: BIRD_HEIGHT,
+            pixels,
+        }
+    }
+
+    /// Returns whether the pixel at (x, y) of the sprite is drawn.
+    pub fn is_opaque(&self, x: u32, y: u32) -> bool {
+        self.pixels[(y * self.width + x) as usize][3] > 0
+    }
+
+    /// Draws the sprite with its top left corner at (x, y), clipped to the frame.
+    pub fn draw(&self, frame: &mut [u8], x: i32, y: i32) -> () {
+        for sprite_y in 0..self.height {
+            for sprite_x in 0..self.width {
+                let pixel = self.pixels[(sprite_y * self.width + sprite_x) as usize];
+                if pixel[3] > 0 {
+                    draw_pixel(frame, x + sprite_x as i32, y + sprite_y as i32, pixel);
+                }
+            }
+        }
+    }
+}
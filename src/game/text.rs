@@ -0,0 +1,263 @@
+use crate::config::{
+    FONT_PIXEL_SIZE_SCREEN_PIXELS, UI_A_COLOR, UI_B_COLOR, UI_G_COLOR, UI_R_COLOR,
+};
+use crate::game::font::{Font, font};
+use crate::game::theme::Palette;
+
+/// Which point of each line the x given to `draw_text` is.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Align {
+    Left,
+    Centre,
+    Right,
+}
+
+/// Drawn under the text to keep it readable over the playfield.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TextEffect {
+    Plain,
+    /// A copy offset down and right by one font pixel.
+    Shadow([u8; 4]),
+    /// A border around each glyph, half a font pixel thick.
+    Outline([u8; 4]),
+}
+
+/// How `draw_text` lays out and colours text.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TextStyle {
+    /// Screen pixels per font pixel.
+    pixel_size: u32,
+    color: [u8; 4],
+    align: Align,
+    effect: TextEffect,
+    /// Lines are wrapped between words to fit this many screen pixels, if set.
+    wrap_width: Option<u32>,
+}
+
+impl TextStyle {
+    /// Left aligned unwrapped text in the UI colour at the default size.
+    pub fn new() -> Self {
+        Self {
+            pixel_size: FONT_PIXEL_SIZE_SCREEN_PIXELS,
+            color: [UI_R_COLOR, UI_G_COLOR, UI_B_COLOR, UI_A_COLOR],
+            align: Align::Left,
+            effect: TextEffect::Plain,
+            wrap_width: None,
+        }
+    }
+
+    pub fn with_scale(mut self, pixel_size: u32) -> Self {
+        self.pixel_size = pixel_size.max(1);
+        self
+    }
+
+    pub fn with_color(mut self, color: [u8; 4]) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn with_align(mut self, align: Align) -> Self {
+        self.align = align;
+        self
+    }
+
+    pub fn with_shadow(mut self, color: [u8; 4]) -> Self {
+        self.effect = TextEffect::Shadow(color);
+        self
+    }
+
+    pub fn with_outline(mut self, color: [u8; 4]) -> Self {
+        self.effect = TextEffect::Outline(color);
+        self
+    }
+
+    /// Outlined text in the palette's colours.
+    pub fn with_palette(self, palette: &Palette) -> Self {
+        self.with_color(palette.text).with_outline(palette.outline)
+    }
+
+    pub fn with_wrap_width(mut self, width: u32) -> Self {
+        self.wrap_width = Some(width);
+        self
+    }
+
+    /// Height of one line in screen pixels.
+    pub fn line_height(&self) -> u32 {
+        font().line_height() * self.pixel_size
+    }
+}
+
+/// Returns the width and height in screen pixels the text takes up when drawn with the style.
+/// Shadows and outlines are not included.
+pub fn measure_text(text: &str, style: &TextStyle) -> (u32, u32) {
+    let font = font();
+    let lines = layout(font, text, style);
+    let width = lines
+        .iter()
+        .map(|line| font.text_width(line) * style.pixel_size)
+        .max()
+        .unwrap_or(0);
+
+    (width, lines.len() as u32 * style.line_height())
+}
+
+/// Draws the text with its first line's top at y, splitting it into lines at newlines and,
+/// if the style has a wrap width, between words.
+/// The style's alignment says whether x is the left edge, centre or right edge of each line.
+pub fn draw_text(frame: &mut [u8], text: &str, x: i32, y: i32, style: &TextStyle) -> () {
+    let font = font();
+    let size = style.pixel_size as i32;
+
+    for (i, line) in layout(font, text, style).iter().enumerate() {
+        let width = (font.text_width(line) * style.pixel_size) as i32;
+        let left = match style.align {
+            Align::Left => x,
+            Align::Centre => x - width / 2,
+            Align::Right => x - width,
+        };
+        let top = y + i as i32 * style.line_height() as i32;
+
+        match style.effect {
+            TextEffect::Plain => (),
+            TextEffect::Shadow(color) => font.draw_line(
+                frame,
+                line,
+                left + size,
+                top + size,
+                style.pixel_size,
+                color,
+            ),
+            TextEffect::Outline(color) => {
+                let thickness = (size / 2).max(1);
+                for (dx, dy) in [
+                    (-1, -1),
+                    (0, -1),
+                    (1, -1),
+                    (-1, 0),
+                    (1, 0),
+                    (-1, 1),
+                    (0, 1),
+                    (1, 1),
+                ] {
+                    font.draw_line(
+                        frame,
+                        line,
+                        left + dx * thickness,
+                        top + dy * thickness,
+                        style.pixel_size,
+                        color,
+                    );
+                }
+            }
+        }
+
+        font.draw_line(frame, line, left, top, style.pixel_size, style.color);
+    }
+}
+
+/// Splits the text into the lines it is drawn as.
+fn layout(font: &Font, text: &str, style: &TextStyle) -> Vec<String> {
+    let Some(wrap_width) = style.wrap_width else {
+        return text.split('\n').map(str::to_string).collect();
+    };
+
+    // Wrap in font pixels so nothing is rounded.
+    let max_width = wrap_width / style.pixel_size;
+    let mut lines = Vec::new();
+    for paragraph in text.split('\n') {
+        let mut current = String::new();
+
+        for word in paragraph.split(' ') {
+            let candidate = if current.is_empty() {
+                word.to_string()
+            } else {
+                format!("{current} {word}")
+            };
+            if font.text_width(&candidate) <= max_width {
+                current = candidate;
+                continue;
+            }
+
+            if !current.is_empty() {
+                lines.push(std::mem::take(&mut current));
+            }
+            // A word wider than the box on its own is broken between characters.
+            for c in word.chars() {
+                if !current.is_empty() && font.text_width(&format!("{current}{c}")) > max_width {
+                    lines.push(std::mem::take(&mut current));
+                }
+                current.push(c);
+            }
+        }
+
+        lines.push(current);
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Align, TextStyle, draw_text, measure_text};
+    use crate::config::{HEIGHT, WIDTH};
+
+    /// The leftmost and rightmost drawn columns.
+    fn drawn_columns(text: &str, x: i32, style: &TextStyle) -> (u32, u32) {
+        let mut frame = vec![0; (WIDTH * HEIGHT * 4) as usize];
+        draw_text(&mut frame, text, x, 10, style);
+
+        let columns: Vec<u32> = frame
+            .chunks_exact(4)
+            .enumerate()
+            .filter(|(_, pixel)| *pixel != [0; 4])
+            .map(|(i, _)| i as u32 % WIDTH)
+            .collect();
+        (
+            *columns.iter().min().unwrap(),
+            *columns.iter().max().unwrap(),
+        )
+    }
+
+    #[test]
+    fn measures_lines_and_newlines() {
+        let style = TextStyle::new().with_scale(2);
+
+        assert_eq!(measure_text("abc", &style), (42, 18));
+        assert_eq!(measure_text("abc\na", &style), (42, 36));
+        assert_eq!(measure_text("", &style), (0, 18));
+    }
+
+    #[test]
+    fn wraps_between_words_and_inside_long_ones() {
+        // Seven font pixels per character at scale 1, so 28 pixels fit four of them.
+        let style = TextStyle::new().with_scale(1).with_wrap_width(28);
+
+        assert_eq!(measure_text("ab cd ef", &style), (14, 27));
+        assert_eq!(measure_text("abcdefghij", &style), (28, 27));
+        assert_eq!(measure_text("ab\n\ncd", &style), (14, 27));
+    }
+
+    #[test]
+    fn alignment_moves_the_anchor() {
+        let style = TextStyle::new().with_scale(1);
+        let (left, _) = drawn_columns("a", 100, &style);
+        let (centred, _) = drawn_columns("a", 100, &style.with_align(Align::Centre));
+        let (right, _) = drawn_columns("a", 100, &style.with_align(Align::Right));
+
+        assert_eq!(left - centred, 3);
+        assert_eq!(left - right, 7);
+    }
+
+    #[test]
+    fn effects_draw_around_the_text() {
+        let style = TextStyle::new().with_scale(4);
+        let (plain_left, plain_right) = drawn_columns("i", 100, &style);
+        let (_, shadow_right) = drawn_columns("i", 100, &style.with_shadow([1, 1, 1, 0xFF]));
+        let (outline_left, outline_right) =
+            drawn_columns("i", 100, &style.with_outline([1, 1, 1, 0xFF]));
+
+        assert_eq!(shadow_right, plain_right + 4);
+        assert_eq!(outline_left, plain_left - 2);
+        assert_eq!(outline_right, plain_right + 2);
+    }
+}
@@ -0,0 +1,65 @@
+use crate::config::{CLASSIC_PALETTE, CONTRAST_PALETTE, NIGHT_PALETTE};
+use std::fmt;
+use std::str::FromStr;
+
+/// Colours of the background and the UI text.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Palette {
+    /// Behind everything, transparent to leave the host's clear colour.
+    pub background: [u8; 4],
+    pub text: [u8; 4],
+    /// Around text so it reads over the playfield.
+    pub outline: [u8; 4],
+    /// Menu items that aren't selected and the best score beside the score.
+    pub muted: [u8; 4],
+    /// Lists and other small text.
+    pub hint: [u8; 4],
+}
+
+/// Colour themes picked in the settings.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Theme {
+    Classic,
+    /// A dark sky with warm text.
+    Night,
+    /// White on black, for readability.
+    Contrast,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 3] = [Theme::Classic, Theme::Night, Theme::Contrast];
+
+    pub fn palette(self) -> Palette {
+        match self {
+            Theme::Classic => CLASSIC_PALETTE,
+            Theme::Night => NIGHT_PALETTE,
+            Theme::Contrast => CONTRAST_PALETTE,
+        }
+    }
+}
+
+impl FromStr for Theme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "classic" => Ok(Theme::Classic),
+            "night" => Ok(Theme::Night),
+            "contrast" => Ok(Theme::Contrast),
+            _ => Err(format!(
+                "unknown theme `{s}`, expected classic, night or contrast"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Theme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Theme::Classic => "classic",
+            Theme::Night => "night",
+            Theme::Contrast => "contrast",
+        };
+        write!(f, "{name}")
+    }
+}
@@ -0,0 +1,48 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// How many fixed simulation steps run per second.
+/// Physics is expressed per second, so every rate plays the same game; higher rates
+/// only reduce the input latency and the integration error.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TickRate {
+    Hz60,
+    Hz120,
+    Hz240,
+}
+
+impl TickRate {
+    pub fn hz(self) -> u32 {
+        match self {
+            TickRate::Hz60 => 60,
+            TickRate::Hz120 => 120,
+            TickRate::Hz240 => 240,
+        }
+    }
+
+    /// Length of a single step in seconds.
+    pub fn dt(self) -> f32 {
+        1.0 / self.hz() as f32
+    }
+}
+
+impl FromStr for TickRate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "60" => Ok(TickRate::Hz60),
+            "120" => Ok(TickRate::Hz120),
+            "240" => Ok(TickRate::Hz240),
+            _ => Err(format!(
+                "unsupported tick rate `{s}`, expected 60, 120 or 240"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for TickRate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.hz())
+    }
+}
@@ -1,6 +1,6 @@
-use std::ops::{Add, Mul};
+use std::ops::{Add, Mul, Sub};
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Vector2 {
     pub x: f32,
     pub y: f32,
@@ -11,10 +11,6 @@ impl Vector2 {
         Self { x, y }
     }
 
-    pub fn up() -> Self {
-        Self { x: 0.0, y: -1.0 }
-    }
-
     pub fn down() -> Self {
         Self { x: 0.0, y: 1.0 }
     }
@@ -22,6 +18,19 @@ impl Vector2 {
     pub fn left() -> Self {
         Self { x: -1.0, y: 0.0 }
     }
+
+    pub fn dot(self, other: Self) -> f32 {
+        self.x * other.x + self.y * other.y
+    }
+
+    pub fn length_squared(self) -> f32 {
+        self.dot(self)
+    }
+
+    /// Linearly interpolates between self (t = 0) and other (t = 1).
+    pub fn lerp(self, other: Self, t: f32) -> Self {
+        self * (1.0 - t) + other * t
+    }
 }
 
 impl Add for Vector2 {
@@ -34,6 +43,16 @@ impl Add for Vector2 {
     }
 }
 
+impl Sub for Vector2 {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+        }
+    }
+}
+
 impl Mul<f32> for Vector2 {
     type Output = Self;
     fn mul(self, s: f32) -> Self {
@@ -0,0 +1,145 @@
+use crate::config::{HIGH_SCORES_FILE_NAME, HIGH_SCORES_KEPT};
+use crate::settings::settings_path;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Error;
+use std::path::{Path, PathBuf};
+
+/// The best scores so far, highest first, persisted one per line.
+/// Each level's best follows as a `level <score> <name>` line.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HighScores {
+    scores: Vec<u32>,
+    /// The best score of each level finished so far, by level name.
+    levels: BTreeMap<String, u32>,
+}
+
+impl HighScores {
+    /// Loads the high scores, starting empty if the file is missing or unreadable.
+    /// Lines that aren't a score are ignored.
+    pub fn load(path: &Path) -> Self {
+        let mut high_scores = Self::default();
+        if let Ok(contents) = fs::read_to_string(path) {
+            for line in contents.lines().map(str::trim) {
+                if let Some((score, name)) = line
+                    .strip_prefix("level ")
+                    .and_then(|level| level.split_once(' '))
+                {
+                    if let Ok(score) = score.parse() {
+                        high_scores.record_level(name, score);
+                    }
+                } else if let Ok(score) = line.parse() {
+                    high_scores.record(score);
+                }
+            }
+        }
+        high_scores
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut contents: String = self
+            .scores
+            .iter()
+            .map(|score| format!("{score}\n"))
+            .collect();
+        for (name, score) in &self.levels {
+            contents += &format!("level {score} {name}\n");
+        }
+        fs::write(path, contents)
+    }
+
+    /// Adds a finished run's score, returning whether it made the table.
+    pub fn record(&mut self, score: u32) -> bool {
+        let rank = self.scores.partition_point(|&kept| kept >= score);
+        if rank >= HIGH_SCORES_KEPT {
+            return false;
+        }
+
+        self.scores.insert(rank, score);
+        self.scores.truncate(HIGH_SCORES_KEPT);
+        true
+    }
+
+    pub fn best(&self) -> u32 {
+        self.scores.first().copied().unwrap_or(0)
+    }
+
+    pub fn scores(&self) -> &[u32] {
+        &self.scores
+    }
+
+    /// Adds the score of a finished level, returning whether it beat the level's best.
+    pub fn record_level(&mut self, name: &str, score: u32) -> bool {
+        match self.levels.get(name) {
+            Some(&best) if best >= score => false,
+            _ => {
+                self.levels.insert(name.to_string(), score);
+                true
+            }
+        }
+    }
+
+    /// The best score of the named level, None if it hasn't been finished.
+    pub fn level_best(&self, name: &str) -> Option<u32> {
+        self.levels.get(name).copied()
+    }
+}
+
+pub fn high_scores_path() -> PathBuf {
+    settings_path().with_file_name(HIGH_SCORES_FILE_NAME)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HighScores;
+    use crate::config::HIGH_SCORES_KEPT;
+
+    #[test]
+    fn keeps_the_best_scores_in_order() {
+        let mut high_scores = HighScores::default();
+        assert_eq!(high_scores.best(), 0);
+
+        for score in 1..=HIGH_SCORES_KEPT as u32 {
+            assert!(high_scores.record(score));
+        }
+        assert!(!high_scores.record(0));
+        assert!(high_scores.record(5));
+
+        assert_eq!(high_scores.best(), HIGH_SCORES_KEPT as u32);
+        assert_eq!(high_scores.scores.len(), HIGH_SCORES_KEPT);
+        assert!(high_scores.scores.windows(2).all(|pair| pair[0] >= pair[1]));
+        assert!(!high_scores.scores.contains(&1));
+    }
+
+    #[test]
+    fn keeps_the_best_score_of_each_level() {
+        let mut high_scores = HighScores::default();
+        assert_eq!(high_scores.level_best("first flight"), None);
+
+        assert!(high_scores.record_level("first flight", 8));
+        assert!(!high_scores.record_level("first flight", 8));
+        assert!(high_scores.record_level("first flight", 11));
+        assert!(!high_scores.record_level("first flight", 3));
+
+        assert_eq!(high_scores.level_best("first flight"), Some(11));
+        assert_eq!(high_scores.best(), 0);
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let path = std::env::temp_dir().join("rust-game-high-scores-test.txt");
+        let mut high_scores = HighScores::default();
+        for score in [3, 12, 7] {
+            high_scores.record(score);
+        }
+        high_scores.record_level("first flight", 9);
+        high_scores.record_level("on the move", 0);
+
+        high_scores.save(&path).unwrap();
+        assert_eq!(HighScores::load(&path), high_scores);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
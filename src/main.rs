@@ -1,56 +1,266 @@
 #![forbid(unsafe_code)]
+// `-> ()` is kept deliberately for readability.
+#![allow(clippy::unused_unit)]
 
+mod cli;
 mod config;
+mod export;
 mod game;
+mod high_scores;
+mod settings;
+mod viewport;
 
-use std::time::Instant;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use config::{FIXED_DT, HEIGHT, WIDTH};
-use game::Game;
-use pixels::{Error, Pixels, SurfaceTexture};
+use cli::Command;
+use config::{FPS_CAPS, HEIGHT, REPLAY_DIRECTORY, WIDTH};
+use game::{Font, FrameStats, Game, Language, Level, Outcome, Replay, TickRate, set_font};
+use high_scores::{HighScores, high_scores_path};
+use pixels::{Error, Pixels, PixelsBuilder, SurfaceTexture};
+use settings::{FullscreenMode, Settings, settings_path};
+use viewport::{ScalingMode, Viewport};
 use winit::{
     dpi::LogicalSize,
-    event::{ElementState, Event, KeyEvent, WindowEvent},
-    event_loop::{ControlFlow, EventLoop},
+    event::{ElementState, Event, KeyEvent, MouseButton, WindowEvent},
+    event_loop::{ControlFlow, EventLoop, EventLoopWindowTarget},
     keyboard::{Key, NamedKey},
-    window::WindowBuilder,
+    window::{Fullscreen, Window, WindowBuilder},
 };
 
-fn main() -> Result<(), Error> {
+fn main() -> ExitCode {
+    let command = match cli::parse(std::env::args().skip(1)) {
+        Ok(command) => command,
+        Err(message) => {
+            eprintln!("{message}\n{}", cli::USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let result = match command {
+        Command::Play {
+            tick_rate,
+            font,
+            language,
+            levels,
+        } => run(tick_rate, font, language, &levels).map_err(|e| e.to_string()),
+        Command::Export {
+            replay,
+            output,
+            options,
+        } => Replay::load(&replay)
+            .and_then(|replay| {
+                if output.extension().is_some_and(|ext| ext == "gif") {
+                    export::export_gif(&replay, &output, &options)
+                } else {
+                    export::export_frames(&replay, &output, &options)
+                }
+            })
+            .map_err(|e| format!("failed to export {}: {e}", replay.display())),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("{message}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Saves the most recent run into the replay directory.
+fn save_replay(game: &Game) -> () {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or_default();
+
+    // Saves in the same millisecond get a counter rather than overwriting each other.
+    for attempt in 0.. {
+        let name = match attempt {
+            0 => format!("replay-{timestamp}.replay"),
+            n => format!("replay-{timestamp}-{n}.replay"),
+        };
+        let path = Path::new(REPLAY_DIRECTORY).join(name);
+
+        match game.replay().save(&path) {
+            Ok(()) => eprintln!("saved replay to {}", path.display()),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+            Err(e) => eprintln!("failed to save replay to {}: {e}", path.display()),
+        }
+        return;
+    }
+}
+
+/// Saves the high scores if a run made it into them since the last save.
+fn save_high_scores(game: &mut Game, path: &Path) -> () {
+    if game.take_high_scores_changed()
+        && let Err(e) = game.high_scores().save(path)
+    {
+        eprintln!("failed to save high scores to {}: {e}", path.display());
+    }
+}
+
+/// Does what the game asked the host to do after an input.
+fn handle_outcome(outcome: Outcome, game: &Game, elwt: &EventLoopWindowTarget<()>) -> () {
+    match outcome {
+        Outcome::Continue => (),
+        Outcome::SaveReplay => save_replay(game),
+        Outcome::Quit => elwt.exit(),
+    }
+}
+
+/// Converts the fullscreen setting into what winit expects.
+fn fullscreen_for(mode: FullscreenMode, window: &Window) -> Option<Fullscreen> {
+    match mode {
+        FullscreenMode::Windowed => None,
+        FullscreenMode::Borderless => Some(Fullscreen::Borderless(None)),
+        FullscreenMode::Exclusive => {
+            // Pick the largest, then fastest, video mode of the current monitor.
+            let video_mode = window.current_monitor().and_then(|monitor| {
+                monitor.video_modes().max_by_key(|mode| {
+                    let size = mode.size();
+                    (size.width * size.height, mode.refresh_rate_millihertz())
+                })
+            });
+            Some(match video_mode {
+                Some(video_mode) => Fullscreen::Exclusive(video_mode),
+                None => Fullscreen::Borderless(None),
+            })
+        }
+    }
+}
+
+fn save_settings(settings: &Settings) -> () {
+    let path = settings_path();
+    if let Err(e) = settings.save(&path) {
+        eprintln!("failed to save settings to {}: {e}", path.display());
+    }
+}
+
+/// Draws all text with the font at `path`, keeping the builtin font if it can't be loaded.
+fn use_font(path: &Path) -> () {
+    match Font::load(path) {
+        Ok(font) => {
+            let _ = set_font(font);
+        }
+        Err(e) => eprintln!("failed to load font {}: {e}", path.display()),
+    }
+}
+
+/// The built-in levels followed by the ones at `paths`, skipping any that can't be loaded.
+fn load_levels(paths: &[PathBuf]) -> Vec<Level> {
+    let mut levels = Level::built_in();
+    for path in paths {
+        match Level::load(path) {
+            // Best scores are kept by name, so two levels can't share one.
+            Ok(level) if levels.iter().any(|other| other.name == level.name) => eprintln!(
+                "failed to load level {}: there is already a level called {}",
+                path.display(),
+                level.name
+            ),
+            Ok(level) => levels.push(level),
+            Err(e) => eprintln!("failed to load level {}: {e}", path.display()),
+        }
+    }
+    levels
+}
+
+fn run(
+    tick_rate: Option<TickRate>,
+    font: Option<PathBuf>,
+    language: Option<Language>,
+    levels: &[PathBuf],
+) -> Result<(), Error> {
     let event_loop = EventLoop::new().unwrap();
+    let settings = Settings::load(&settings_path());
+    let tick_rate = tick_rate.unwrap_or(settings.tick_rate);
+    if let Some(path) = font.or(settings.font.clone()) {
+        use_font(&path);
+    }
 
     let window = {
-        let size = LogicalSize::new(WIDTH as f64, HEIGHT as f64);
         WindowBuilder::new()
             .with_title("Flappy")
-            .with_inner_size(size)
-            .with_resizable(false)
+            .with_inner_size(window_size(settings.display.window_scale))
+            .with_min_inner_size(LogicalSize::new(WIDTH as f64 / 3.0, HEIGHT as f64 / 3.0))
+            .with_resizable(true)
             .build(&event_loop)
             .unwrap()
     };
+    window.set_fullscreen(fullscreen_for(settings.display.fullscreen, &window));
 
     // Rendering and game state.
     let mut pixels: Option<Pixels> = None;
-    let mut game: Game = Game::new();
+    let mut viewport = Viewport::new(settings.display.scaling, WIDTH, HEIGHT);
+    // Window scale last applied, the settings scene can change it.
+    let mut window_scale = settings.display.window_scale;
+    // Offscreen playfield, only used when the viewport has to scale it on the CPU.
+    let mut playfield: Vec<u8> = vec![0; (WIDTH * HEIGHT * 4) as usize];
+    // Cursor position in playfield coordinates, None when it is over the letterbox.
+    let mut cursor: Option<(f32, f32)> = None;
+    let high_scores_path = high_scores_path();
+    let mut game: Game = Game::new()
+        .with_tick_rate(tick_rate)
+        .with_settings(settings)
+        .with_high_scores(HighScores::load(&high_scores_path))
+        .with_levels(load_levels(levels));
+    if let Some(language) = language {
+        // Only for this session, the settings keep their language.
+        game = game.with_language(language);
+    }
+    let fixed_dt = game.tick_rate().dt();
 
     // Clock set up
     let mut last: Instant = Instant::now();
     let mut accum: f32 = 0.0;
+    let mut last_frame: Instant = Instant::now();
+    // For the debug overlay's stats.
+    let mut last_redraw: Instant = Instant::now();
+    let mut updates_since_redraw: u32 = 0;
 
     event_loop.set_control_flow(ControlFlow::Poll);
 
     let res = event_loop.run(|event, elwt| {
         match event {
-            Event::Resumed => {
+            Event::Resumed if pixels.is_none() => {
                 // Have to create pixels inside the closure because it references window
                 // If it was created outside the loop and it reference window there, it would be referencing
                 // the memory where window was in main's stack (but it got moved to this closure).
 
                 // This is the place to do it because Resumed marks the start of the window.
-                if pixels.is_none() {
-                    let size = window.inner_size();
-                    let surface = SurfaceTexture::new(size.width, size.height, &window);
-                    pixels = Some(Pixels::new(WIDTH as u32, HEIGHT as u32, surface).unwrap());
+                let size = window.inner_size();
+                let display = game.settings().display;
+                viewport = Viewport::new(display.scaling, size.width, size.height);
+                let (buffer_width, buffer_height) = viewport.buffer_size();
+                let surface = SurfaceTexture::new(size.width, size.height, &window);
+                pixels = Some(
+                    PixelsBuilder::new(buffer_width, buffer_height, surface)
+                        .enable_vsync(display.vsync)
+                        .build()
+                        .unwrap(),
+                );
+            }
+            Event::WindowEvent {
+                event: WindowEvent::Resized(size),
+                ..
+            } => {
+                // Minimised windows report a zero size, which the surface can't take.
+                if size.width == 0 || size.height == 0 {
+                    return;
+                }
+
+                if let Some(p) = pixels.as_mut() {
+                    p.resize_surface(size.width, size.height).unwrap();
+
+                    let old_buffer_size = viewport.buffer_size();
+                    viewport = Viewport::new(viewport.mode(), size.width, size.height);
+                    let (buffer_width, buffer_height) = viewport.buffer_size();
+                    if viewport.buffer_size() != old_buffer_size {
+                        p.resize_buffer(buffer_width, buffer_height).unwrap();
+                    }
                 }
             }
             Event::WindowEvent {
@@ -59,29 +269,56 @@ fn main() -> Result<(), Error> {
             } => {
                 elwt.exit();
             }
+            Event::LoopExiting => {
+                // However the game is closed, nothing is lost. The pixels surface is dropped
+                // with the closure once the loop returns.
+                save_high_scores(&mut game, &high_scores_path);
+                save_settings(game.settings());
+            }
             Event::AboutToWait => {
                 // Application update code.
-
-                // Queue a RedrawRequested event.
-                //
-                // You only need to call this if you've determined that you need to redraw in
-                // applications which do not always need to. Applications that redraw continuously
-                // can render here instead.
-                // time
                 let now = Instant::now();
                 let mut dt = (now - last).as_secs_f32();
                 last = now;
                 if dt > 0.25 {
                     dt = 0.25;
                 }
-                accum += dt;
+                accum += dt * game.timescale();
 
-                while accum >= FIXED_DT {
-                    game.update(FIXED_DT);
-                    accum -= FIXED_DT;
+                while accum >= fixed_dt {
+                    game.update(fixed_dt);
+                    accum -= fixed_dt;
+                    updates_since_redraw += 1;
+                }
+
+                save_high_scores(&mut game, &high_scores_path);
+                if game.take_settings_changed() {
+                    let display = game.settings().display;
+                    // Only resize when the size changed, so other changes keep the
+                    // player's own window size.
+                    if display.window_scale != window_scale {
+                        window_scale = display.window_scale;
+                        let _ = window.request_inner_size(window_size(window_scale));
+                    }
+                    save_settings(game.settings());
                 }
 
-                window.request_redraw();
+                // Queue a RedrawRequested event, unless the frame cap says it's too early.
+                // When capped, sleep until the next frame is due instead of spinning.
+                match game.settings().display.fps_cap {
+                    Some(fps) => {
+                        let next_frame = last_frame + Duration::from_secs_f32(1.0 / fps as f32);
+                        if now >= next_frame {
+                            last_frame = now;
+                            window.request_redraw();
+                        }
+                        elwt.set_control_flow(ControlFlow::WaitUntil(next_frame.max(now)));
+                    }
+                    None => {
+                        window.request_redraw();
+                        elwt.set_control_flow(ControlFlow::Poll);
+                    }
+                }
             }
             Event::WindowEvent {
                 event: WindowEvent::RedrawRequested,
@@ -92,58 +329,226 @@ fn main() -> Result<(), Error> {
                 // It's preferable for applications that do not render continuously to render in
                 // this event rather than in AboutToWait, since rendering in here allows
                 // the program to gracefully handle redraws requested by the OS.
+                let now = Instant::now();
+                game.set_frame_stats(FrameStats {
+                    updates: updates_since_redraw,
+                    frame_time: now - last_redraw,
+                });
+                last_redraw = now;
+                updates_since_redraw = 0;
+
                 if let Some(p) = pixels.as_mut() {
                     let frame = p.frame_mut();
+                    // How far the clock is into the next update.
+                    let alpha = accum / fixed_dt;
 
-                    // Clear the frame from the previous drawing:
-                    frame.fill(0);
+                    if viewport.needs_blit() {
+                        playfield.fill(0);
+                        game.draw(&mut playfield, alpha);
+                        viewport.blit(&playfield, frame);
+                    } else {
+                        // Clear the frame from the previous drawing:
+                        frame.fill(0);
 
-                    game.draw(frame);
+                        game.draw(frame, alpha);
+                    }
                     let _ = p.render();
                 }
             }
+            Event::WindowEvent {
+                event: WindowEvent::CursorMoved { position, .. },
+                ..
+            } => {
+                cursor = pixels.as_ref().and_then(|p| {
+                    p.window_pos_to_pixel(position.into())
+                        .ok()
+                        .map(|pixel| viewport.buffer_to_playfield(pixel))
+                });
+                if let Some((x, y)) = cursor {
+                    game.pointer_moved(x, y);
+                }
+            }
+            Event::WindowEvent {
+                event: WindowEvent::CursorLeft { .. },
+                ..
+            } => {
+                cursor = None;
+            }
+            Event::WindowEvent {
+                event:
+                    WindowEvent::MouseInput {
+                        state: ElementState::Pressed,
+                        button: MouseButton::Left,
+                        ..
+                    },
+                ..
+            } if !game.console_is_open() => {
+                // Clicks on the letterbox bars are ignored.
+                if let Some((x, y)) = cursor {
+                    handle_outcome(game.click(x, y), &game, elwt);
+                }
+            }
             Event::WindowEvent {
                 event:
                     WindowEvent::KeyboardInput {
                         event:
                             KeyEvent {
-                                logical_key: Key::Named(NamedKey::Space),
-                                state,
+                                logical_key,
+                                text,
+                                state: ElementState::Pressed,
                                 repeat,
                                 ..
                             },
                         ..
                     },
                 ..
-            } => {
-                if state == ElementState::Pressed && !repeat {
-                    game.space_bar_hit();
+            } if game.console_is_open() => match logical_key {
+                // Typing repeats, and every other key goes to the console while it is open.
+                Key::Named(NamedKey::Escape) => game.console().toggle(),
+                key if is_bound(&key, &game.settings().keys.console) => {
+                    if !repeat {
+                        game.console().toggle();
+                    }
                 }
-            }
+                Key::Named(NamedKey::Enter) => game.submit_console(),
+                Key::Named(NamedKey::Backspace) => game.console().backspace(),
+                Key::Named(NamedKey::ArrowUp) => game.console().history_previous(),
+                Key::Named(NamedKey::ArrowDown) => game.console().history_next(),
+                _ => {
+                    if let Some(text) = text {
+                        game.console().type_text(&text);
+                    }
+                }
+            },
             Event::WindowEvent {
                 event:
                     WindowEvent::KeyboardInput {
                         event:
                             KeyEvent {
-                                logical_key: Key::Character(ch),
-                                state,
-                                repeat,
+                                logical_key,
+                                state: ElementState::Pressed,
+                                repeat: false,
                                 ..
                             },
                         ..
                     },
                 ..
-            } => {
-                if state == ElementState::Pressed && !repeat {
-                    match ch.as_str() {
-                        "y" | "Y" => game.y_key_hit(),
-                        "n" | "N" => game.n_key_hit(),
-                        _ => (),
+            } if game.is_rebinding() => match logical_key {
+                // The settings scene is waiting for the key to bind.
+                Key::Named(NamedKey::Escape) => game.escape_key_hit(),
+                key if !is_reserved(&key) => game.bind_key(key_name(&key)),
+                _ => (),
+            },
+            Event::WindowEvent {
+                event:
+                    WindowEvent::KeyboardInput {
+                        event:
+                            KeyEvent {
+                                logical_key,
+                                state: ElementState::Pressed,
+                                repeat: false,
+                                ..
+                            },
+                        ..
+                    },
+                ..
+            } => match logical_key {
+                key if is_bound(&key, &game.settings().keys.flap) => game.space_bar_hit(),
+                key if is_bound(&key, &game.settings().keys.console) => game.console().toggle(),
+                Key::Named(NamedKey::ArrowUp) => game.up_key_hit(),
+                Key::Named(NamedKey::ArrowDown) => game.down_key_hit(),
+                Key::Named(NamedKey::ArrowLeft) => game.left_key_hit(),
+                Key::Named(NamedKey::ArrowRight) => game.right_key_hit(),
+                Key::Named(NamedKey::Enter) => handle_outcome(game.enter_key_hit(), &game, elwt),
+                Key::Named(NamedKey::Escape) => game.escape_key_hit(),
+                Key::Named(NamedKey::F3) => game.toggle_debug_overlay(),
+                Key::Named(NamedKey::F11) => {
+                    let display = &mut game.settings_mut().display;
+                    display.fullscreen = display.fullscreen.next();
+                    window.set_fullscreen(fullscreen_for(display.fullscreen, &window));
+                    save_settings(game.settings());
+                }
+                Key::Named(NamedKey::F10) => {
+                    let display = &mut game.settings_mut().display;
+                    display.scaling = match display.scaling {
+                        ScalingMode::Integer => ScalingMode::Aspect,
+                        ScalingMode::Aspect => ScalingMode::Integer,
+                    };
+                    let size = window.inner_size();
+                    viewport = Viewport::new(display.scaling, size.width, size.height);
+
+                    if let Some(p) = pixels.as_mut() {
+                        let (buffer_width, buffer_height) = viewport.buffer_size();
+                        p.resize_buffer(buffer_width, buffer_height).unwrap();
                     }
+                    save_settings(game.settings());
                 }
-            }
+                Key::Named(NamedKey::F9) => {
+                    let display = &mut game.settings_mut().display;
+                    display.vsync = !display.vsync;
+                    if let Some(p) = pixels.as_mut() {
+                        p.enable_vsync(display.vsync);
+                    }
+                    save_settings(game.settings());
+                }
+                Key::Named(NamedKey::F8) => {
+                    let display = &mut game.settings_mut().display;
+                    display.fps_cap = next_fps_cap(display.fps_cap);
+                    save_settings(game.settings());
+                }
+                _ => (),
+            },
             _ => (),
         }
     });
     res.map_err(|e| Error::UserDefined(Box::new(e)))
 }
+
+/// The window's size at a scale in percent of the playfield.
+fn window_size(scale: u32) -> LogicalSize<f64> {
+    let scale = scale as f64 / 100.0;
+    LogicalSize::new(WIDTH as f64 * scale, HEIGHT as f64 * scale)
+}
+
+/// Names a key the way key bindings store it: the character it types, or the named key
+/// in lower case such as `space`.
+fn key_name(key: &Key) -> Option<String> {
+    match key {
+        Key::Character(ch) => Some(ch.to_lowercase()),
+        Key::Named(named) => Some(format!("{named:?}").to_lowercase()),
+        _ => None,
+    }
+}
+
+fn is_bound(key: &Key, binding: &str) -> bool {
+    key_name(key).is_some_and(|name| name == binding)
+}
+
+/// Keys the menus and display toggles use, which can't be bound to anything else.
+fn is_reserved(key: &Key) -> bool {
+    matches!(
+        key,
+        Key::Named(
+            NamedKey::ArrowUp
+                | NamedKey::ArrowDown
+                | NamedKey::ArrowLeft
+                | NamedKey::ArrowRight
+                | NamedKey::Enter
+                | NamedKey::Escape
+                | NamedKey::F3
+                | NamedKey::F8
+                | NamedKey::F9
+                | NamedKey::F10
+                | NamedKey::F11
+        )
+    )
+}
+
+/// Cycles through the frame caps offered by F8.
+fn next_fps_cap(fps_cap: Option<u32>) -> Option<u32> {
+    let position = FPS_CAPS.iter().position(|&cap| cap == fps_cap);
+    match position {
+        Some(i) => FPS_CAPS[(i + 1) % FPS_CAPS.len()],
+        None => FPS_CAPS[0],
+    }
+}
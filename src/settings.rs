@@ -0,0 +1,301 @@
+use crate::config::{
+    DEFAULT_CEILING_BEHAVIOUR, DEFAULT_COLLIDER_SHAPE, DEFAULT_CONSOLE_KEY, DEFAULT_DIFFICULTY,
+    DEFAULT_FLAP_KEY, DEFAULT_FPS_CAP, DEFAULT_LANGUAGE, DEFAULT_PHYSICS_PRESET,
+    DEFAULT_SCORE_POSITION, DEFAULT_THEME, DEFAULT_TICK_RATE, DEFAULT_VOLUME, DEFAULT_WINDOW_SCALE,
+    SCALING_MODE, SETTINGS_FILE_NAME, WINDOW_SCALES,
+};
+use crate::game::{
+    CeilingBehaviour, ColliderShape, Difficulty, Language, PhysicsPreset, ScorePosition, Theme,
+    TickRate,
+};
+use crate::viewport::ScalingMode;
+use std::fs;
+use std::io::Error;
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FullscreenMode {
+    Windowed,
+    /// A borderless window covering the current monitor.
+    Borderless,
+    /// Takes over the monitor's video mode.
+    Exclusive,
+}
+
+impl FullscreenMode {
+    /// The mode F11 switches to from this one.
+    pub fn next(self) -> Self {
+        match self {
+            FullscreenMode::Windowed => FullscreenMode::Borderless,
+            FullscreenMode::Borderless => FullscreenMode::Exclusive,
+            FullscreenMode::Exclusive => FullscreenMode::Windowed,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DisplaySettings {
+    pub fullscreen: FullscreenMode,
+    pub vsync: bool,
+    /// Maximum frames drawn per second, None for uncapped.
+    pub fps_cap: Option<u32>,
+    pub scaling: ScalingMode,
+    /// Size of the window as a percentage of the playfield, one of `WINDOW_SCALES`.
+    pub window_scale: u32,
+}
+
+/// Keys bound to actions, by name: the character typed or a named key such as `space`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyBindings {
+    pub flap: String,
+    pub console: String,
+}
+
+/// User settings, persisted as `key = value` lines in the settings file.
+/// Unknown keys and invalid values are ignored so old or hand-edited files still load.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Settings {
+    pub display: DisplaySettings,
+    pub tick_rate: TickRate,
+    pub physics: PhysicsPreset,
+    pub ceiling: CeilingBehaviour,
+    pub collider: ColliderShape,
+    /// A BDF font to draw text with, None for the builtin one.
+    pub font: Option<PathBuf>,
+    pub score_position: ScorePosition,
+    pub language: Language,
+    pub difficulty: Difficulty,
+    /// Whether the pipes speed up and narrow as the score rises.
+    pub progressive: bool,
+    pub theme: Theme,
+    /// Sound volume in percent. There are no sounds yet, so it is only saved for now.
+    pub volume: u32,
+    /// Whether the frame rate is shown in the corner.
+    pub show_fps: bool,
+    pub keys: KeyBindings,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            display: DisplaySettings {
+                fullscreen: FullscreenMode::Windowed,
+                vsync: true,
+                fps_cap: DEFAULT_FPS_CAP,
+                scaling: SCALING_MODE,
+                window_scale: DEFAULT_WINDOW_SCALE,
+            },
+            tick_rate: DEFAULT_TICK_RATE,
+            physics: DEFAULT_PHYSICS_PRESET,
+            ceiling: DEFAULT_CEILING_BEHAVIOUR,
+            collider: DEFAULT_COLLIDER_SHAPE,
+            font: None,
+            score_position: DEFAULT_SCORE_POSITION,
+            language: DEFAULT_LANGUAGE,
+            difficulty: DEFAULT_DIFFICULTY,
+            progressive: false,
+            theme: DEFAULT_THEME,
+            volume: DEFAULT_VOLUME,
+            show_fps: false,
+            keys: KeyBindings {
+                flap: DEFAULT_FLAP_KEY.to_string(),
+                console: DEFAULT_CONSOLE_KEY.to_string(),
+            },
+        }
+    }
+}
+
+impl Settings {
+    /// Loads the settings, falling back to the defaults if the file is missing or unreadable.
+    pub fn load(path: &Path) -> Self {
+        match fs::read_to_string(path) {
+            Ok(contents) => Self::parse(&contents),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.serialize())
+    }
+
+    fn parse(contents: &str) -> Self {
+        let mut settings = Self::default();
+
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                eprintln!("ignoring invalid settings line `{line}`");
+                continue;
+            };
+
+            if settings.set(key.trim(), value.trim()).is_none() {
+                eprintln!("ignoring invalid setting `{line}`");
+            }
+        }
+
+        settings
+    }
+
+    /// Applies a single setting, returning None if the key or value is invalid.
+    fn set(&mut self, key: &str, value: &str) -> Option<()> {
+        let display = &mut self.display;
+        match key {
+            "fullscreen" => {
+                display.fullscreen = match value {
+                    "windowed" => FullscreenMode::Windowed,
+                    "borderless" => FullscreenMode::Borderless,
+                    "exclusive" => FullscreenMode::Exclusive,
+                    _ => return None,
+                }
+            }
+            "vsync" => display.vsync = value.parse().ok()?,
+            "fps_cap" => {
+                display.fps_cap = match value {
+                    "off" => None,
+                    _ => Some(value.parse().ok().filter(|&fps| fps > 0)?),
+                }
+            }
+            "scaling" => {
+                display.scaling = match value {
+                    "integer" => ScalingMode::Integer,
+                    "aspect" => ScalingMode::Aspect,
+                    _ => return None,
+                }
+            }
+            "window_scale" => {
+                display.window_scale = value.parse().ok().filter(|s| WINDOW_SCALES.contains(s))?
+            }
+            "tick_rate" => self.tick_rate = value.parse().ok()?,
+            "physics" => self.physics = value.parse().ok()?,
+            "ceiling" => self.ceiling = value.parse().ok()?,
+            "collider" => self.collider = value.parse().ok()?,
+            "score_position" => self.score_position = value.parse().ok()?,
+            "language" => self.language = value.parse().ok()?,
+            "difficulty" => self.difficulty = value.parse().ok()?,
+            "progressive" => self.progressive = value.parse().ok()?,
+            "theme" => self.theme = value.parse().ok()?,
+            "volume" => self.volume = value.parse().ok().filter(|&volume| volume <= 100)?,
+            "show_fps" => self.show_fps = value.parse().ok()?,
+            "flap_key" if !value.is_empty() => self.keys.flap = value.to_string(),
+            "console_key" if !value.is_empty() => self.keys.console = value.to_string(),
+            "font" => {
+                self.font = match value {
+                    "builtin" => None,
+                    _ => Some(PathBuf::from(value)),
+                }
+            }
+            _ => return None,
+        }
+        Some(())
+    }
+
+    fn serialize(&self) -> String {
+        let display = &self.display;
+        let fullscreen = match display.fullscreen {
+            FullscreenMode::Windowed => "windowed",
+            FullscreenMode::Borderless => "borderless",
+            FullscreenMode::Exclusive => "exclusive",
+        };
+        let fps_cap = match display.fps_cap {
+            Some(fps) => fps.to_string(),
+            None => "off".to_string(),
+        };
+        let font = match &self.font {
+            Some(path) => path.display().to_string(),
+            None => "builtin".to_string(),
+        };
+        let scaling = match display.scaling {
+            ScalingMode::Integer => "integer",
+            ScalingMode::Aspect => "aspect",
+        };
+
+        format!(
+            "fullscreen = {fullscreen}\nvsync = {}\nfps_cap = {fps_cap}\nscaling = {scaling}\n\
+             window_scale = {}\ntick_rate = {}\nphysics = {}\nceiling = {}\n\
+             collider = {}\nfont = {font}\nscore_position = {}\nlanguage = {}\n\
+             difficulty = {}\nprogressive = {}\ntheme = {}\nvolume = {}\nshow_fps = {}\n\
+             flap_key = {}\nconsole_key = {}\n",
+            display.vsync,
+            display.window_scale,
+            self.tick_rate,
+            self.physics,
+            self.ceiling,
+            self.collider,
+            self.score_position,
+            self.language,
+            self.difficulty,
+            self.progressive,
+            self.theme,
+            self.volume,
+            self.show_fps,
+            self.keys.flap,
+            self.keys.console
+        )
+    }
+}
+
+/// Returns where the settings file lives: the platform's config directory if one can be
+/// found, otherwise the working directory.
+pub fn settings_path() -> PathBuf {
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("APPDATA").map(PathBuf::from))
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")));
+
+    match config_dir {
+        Some(dir) => dir.join("rust-game").join(SETTINGS_FILE_NAME),
+        None => PathBuf::from(SETTINGS_FILE_NAME),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FullscreenMode, Settings};
+    use crate::game::{
+        CeilingBehaviour, ColliderShape, Difficulty, Language, PhysicsPreset, ScorePosition, Theme,
+        TickRate,
+    };
+    use crate::viewport::ScalingMode;
+    use std::path::PathBuf;
+
+    #[test]
+    fn serialize_and_parse_round_trip() {
+        let mut settings = Settings::default();
+        settings.display.fullscreen = FullscreenMode::Exclusive;
+        settings.display.vsync = false;
+        settings.display.fps_cap = None;
+        settings.display.scaling = ScalingMode::Aspect;
+        settings.display.window_scale = 150;
+        settings.tick_rate = TickRate::Hz240;
+        settings.physics = PhysicsPreset::Floaty;
+        settings.ceiling = CeilingBehaviour::Clamp;
+        settings.collider = ColliderShape::Capsule;
+        settings.score_position = ScorePosition::TopLeft;
+        settings.language = Language::Spanish;
+        settings.font = Some(PathBuf::from("assets/fonts/proportional.bdf"));
+        settings.difficulty = Difficulty::Hard;
+        settings.progressive = true;
+        settings.theme = Theme::Night;
+        settings.volume = 30;
+        settings.show_fps = true;
+        settings.keys.flap = "w".to_string();
+        settings.keys.console = "tab".to_string();
+
+        assert_eq!(Settings::parse(&settings.serialize()), settings);
+    }
+
+    #[test]
+    fn invalid_lines_keep_the_defaults() {
+        let settings = Settings::parse(
+            "vsync = maybe\nfps_cap = 0\nnonsense\ncolour = red\nvolume = 101\nwindow_scale = 42\n",
+        );
+
+        assert_eq!(settings, Settings::default());
+    }
+}
@@ -0,0 +1,139 @@
+use crate::config::{HEIGHT, WIDTH};
+
+/// How the logical `WIDTH`x`HEIGHT` playfield is fitted into the window.
+/// Both modes keep the aspect ratio and letterbox the leftover space.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScalingMode {
+    /// Scale by whole multiples only, so every playfield pixel is the same size.
+    /// Windows smaller than the playfield scale it down as in aspect mode instead.
+    Integer,
+    /// Scale as large as the window allows.
+    Aspect,
+}
+
+/// Maps between the playfield and the pixels buffer.
+///
+/// In integer mode the buffer is the playfield itself and pixels' scaling renderer
+/// takes care of the integer scaling and centring. In aspect mode, and in integer mode
+/// when the window is too small for even 1x, the buffer is the scaled playfield size,
+/// the playfield is scaled into it on the CPU and pixels draws it centred at 1:1.
+pub struct Viewport {
+    mode: ScalingMode,
+    buffer_width: u32,
+    buffer_height: u32,
+}
+
+impl Viewport {
+    pub fn new(mode: ScalingMode, surface_width: u32, surface_height: u32) -> Self {
+        let scale =
+            (surface_width as f32 / WIDTH as f32).min(surface_height as f32 / HEIGHT as f32);
+        let (buffer_width, buffer_height) = match mode {
+            // Pixels' renderer crops rather than shrinks a buffer bigger than the window.
+            ScalingMode::Integer if scale >= 1.0 => (WIDTH, HEIGHT),
+            ScalingMode::Integer | ScalingMode::Aspect => (
+                ((WIDTH as f32 * scale).round() as u32).max(1),
+                ((HEIGHT as f32 * scale).round() as u32).max(1),
+            ),
+        };
+
+        Self {
+            mode,
+            buffer_width,
+            buffer_height,
+        }
+    }
+
+    pub fn mode(&self) -> ScalingMode {
+        self.mode
+    }
+
+    pub fn buffer_size(&self) -> (u32, u32) {
+        (self.buffer_width, self.buffer_height)
+    }
+
+    /// Whether the playfield has to be drawn offscreen and blitted into the buffer.
+    pub fn needs_blit(&self) -> bool {
+        self.buffer_size() != (WIDTH, HEIGHT)
+    }
+
+    /// Scales the playfield into the buffer with nearest-neighbour sampling.
+    pub fn blit(&self, playfield: &[u8], frame: &mut [u8]) -> () {
+        let columns: Vec<usize> = (0..self.buffer_width)
+            .map(|x| (x * WIDTH / self.buffer_width) as usize * 4)
+            .collect();
+
+        for (y, row) in frame
+            .chunks_exact_mut(self.buffer_width as usize * 4)
+            .enumerate()
+        {
+            let source_y = y as u32 * HEIGHT / self.buffer_height;
+            let source_row = &playfield[(source_y * WIDTH * 4) as usize..];
+
+            for (pixel, &source_x) in row.chunks_exact_mut(4).zip(&columns) {
+                pixel.copy_from_slice(&source_row[source_x..source_x + 4]);
+            }
+        }
+    }
+
+    /// Maps a position in the pixels buffer (e.g. from `Pixels::window_pos_to_pixel`)
+    /// to playfield coordinates.
+    pub fn buffer_to_playfield(&self, position: (usize, usize)) -> (f32, f32) {
+        (
+            position.0 as f32 * WIDTH as f32 / self.buffer_width as f32,
+            position.1 as f32 * HEIGHT as f32 / self.buffer_height as f32,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ScalingMode, Viewport};
+    use crate::config::{HEIGHT, WIDTH};
+
+    #[test]
+    fn integer_mode_keeps_the_playfield_buffer() {
+        let viewport = Viewport::new(ScalingMode::Integer, 2560, 1440);
+
+        assert_eq!(viewport.buffer_size(), (WIDTH, HEIGHT));
+        assert!(!viewport.needs_blit());
+        assert_eq!(viewport.buffer_to_playfield((450, 300)), (450.0, 300.0));
+    }
+
+    #[test]
+    fn integer_mode_shrinks_into_small_windows() {
+        let viewport = Viewport::new(ScalingMode::Integer, 600, 600);
+
+        assert_eq!(viewport.buffer_size(), (600, 400));
+        assert!(viewport.needs_blit());
+        assert_eq!(
+            viewport.buffer_to_playfield((600, 400)),
+            (WIDTH as f32, HEIGHT as f32)
+        );
+    }
+
+    #[test]
+    fn aspect_mode_fits_the_smaller_dimension() {
+        let viewport = Viewport::new(ScalingMode::Aspect, 1920, 1080);
+
+        assert_eq!(viewport.buffer_size(), (1620, 1080));
+        assert_eq!(viewport.buffer_to_playfield((810, 540)), (450.0, 300.0));
+
+        let playfield: Vec<u8> = (0..WIDTH * HEIGHT)
+            .flat_map(|i| [(i % WIDTH) as u8, (i / WIDTH) as u8, 0, 0xFF])
+            .collect();
+        let mut frame = vec![0; 1620 * 1080 * 4];
+        viewport.blit(&playfield, &mut frame);
+
+        // The bottom right buffer pixel samples the bottom right playfield pixel.
+        let last = frame.len() - 4;
+        assert_eq!(
+            frame[last..],
+            [
+                ((WIDTH - 1) % 256) as u8,
+                ((HEIGHT - 1) % 256) as u8,
+                0,
+                0xFF
+            ]
+        );
+    }
+}